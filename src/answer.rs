@@ -0,0 +1,62 @@
+use std::fmt;
+
+// Every day used to finish with its own println!, which made it impossible
+// to do anything with the result other than read it. Now every solver
+// hands back an Answer and the runner decides how to show it.
+
+/// The result of solving one part of a puzzle. Almost everything is a
+/// number, but a few days have more to say (like where the dial ended up
+/// on day 1), so those can return some text instead.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Int(i128),
+    Text(String),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Answer::Int(n) => write!(f, "{}", n),
+            Answer::Text(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+// Make it easy to turn whatever integer type a day ended up using
+// into an Answer with .into()
+macro_rules! answer_from_int {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(val: $t) -> Self {
+                    Answer::Int(val as i128)
+                }
+            }
+        )*
+    };
+}
+
+answer_from_int!(i32, i64, i128, isize, u32, u64, usize);
+
+// A u128 might not fit in an i128, so anything too big gets shown as text
+// rather than wrapping around to a negative number
+impl From<u128> for Answer {
+    fn from(val: u128) -> Self {
+        match i128::try_from(val) {
+            Ok(n) => Answer::Int(n),
+            Err(_) => Answer::Text(val.to_string()),
+        }
+    }
+}
+
+impl From<String> for Answer {
+    fn from(val: String) -> Self {
+        Answer::Text(val)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(val: &str) -> Self {
+        Answer::Text(val.to_string())
+    }
+}
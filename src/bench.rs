@@ -0,0 +1,171 @@
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use crate::error::ParseError;
+use crate::solver::{OutputFormat, Solver};
+
+// A few of the days have long comments about how fast one approach should
+// be compared to another, but nothing ever measured it. This runs each
+// solver a bunch of times and reports how long parsing took on its own and
+// how long the whole solve took, so alternatives can be compared with
+// actual numbers. Remember to use `cargo run --release` or the numbers
+// won't mean much.
+
+/// Summary of how long something took over all of the iterations
+#[derive(Debug, Clone, Copy)]
+pub struct Timings {
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+}
+
+impl Timings {
+    fn from_samples(mut samples: Vec<Duration>) -> Self {
+        samples.sort();
+        let total: Duration = samples.iter().sum();
+
+        Timings {
+            min: samples[0],
+            median: samples[samples.len() / 2],
+            mean: total / samples.len() as u32,
+        }
+    }
+}
+
+/// How one solver did. `solve` is the full solve (which includes parsing),
+/// `parse` is just the parsing step for days that have one.
+pub struct BenchResult {
+    pub key: String,
+    pub name: &'static str,
+    pub iterations: u32,
+    pub parse: Option<Timings>,
+    pub solve: Timings,
+}
+
+impl BenchResult {
+    /// The median time spent solving once the input was parsed
+    pub fn solve_only(&self) -> Duration {
+        match self.parse {
+            Some(parse) => self.solve.median.saturating_sub(parse.median),
+            None => self.solve.median,
+        }
+    }
+}
+
+/// Time parsing and solving the input `iterations` times each. There's one
+/// untimed warm up run first, which is also where a bad input gets caught.
+pub fn bench(solver: &dyn Solver, content: &str, iterations: u32) -> Result<BenchResult, ParseError> {
+    let iterations = iterations.max(1);
+
+    solver.solve(content)?;
+
+    let parse = match solver.parse(content) {
+        Some(_) => {
+            let mut samples = vec![];
+            for _ in 0..iterations {
+                let start = Instant::now();
+                let _ = black_box(solver.parse(black_box(content)));
+                samples.push(start.elapsed());
+            }
+            Some(Timings::from_samples(samples))
+        }
+        None => None,
+    };
+
+    let mut samples = vec![];
+    for _ in 0..iterations {
+        let start = Instant::now();
+        let _ = black_box(solver.solve(black_box(content)));
+        samples.push(start.elapsed());
+    }
+
+    Ok(BenchResult {
+        key: solver.key(),
+        name: solver.name(),
+        iterations,
+        parse,
+        solve: Timings::from_samples(samples),
+    })
+}
+
+pub fn print_results(results: &[BenchResult], format: OutputFormat) {
+    match format {
+        OutputFormat::Table => print_table(results),
+        OutputFormat::Csv => print_csv(results),
+        OutputFormat::Json => print_json(results),
+    }
+}
+
+fn print_table(results: &[BenchResult]) {
+    println!("{:<12} {:<48} {:>6} {:>14} {:>14} {:>14} {:>14}", "day-part", "name", "iters", "parse", "solve only", "total", "total min");
+
+    for result in results {
+        let parse = match result.parse {
+            Some(parse) => format!("{:?}", parse.median),
+            None => "-".to_string(),
+        };
+        println!(
+            "{:<12} {:<48} {:>6} {:>14} {:>14} {:>14} {:>14}",
+            result.key,
+            result.name,
+            result.iterations,
+            parse,
+            format!("{:?}", result.solve_only()),
+            format!("{:?}", result.solve.median),
+            format!("{:?}", result.solve.min),
+        );
+    }
+}
+
+// All of the machine readable times are in nanoseconds. Days without a
+// separate parsing step get empty parse columns (or null in json).
+
+const CSV_HEADER: &str = "key,name,iterations,parse_min_ns,parse_median_ns,parse_mean_ns,solve_min_ns,solve_median_ns,solve_mean_ns,solve_only_median_ns";
+
+fn print_csv(results: &[BenchResult]) {
+    println!("{}", CSV_HEADER);
+
+    for result in results {
+        let parse = match result.parse {
+            Some(parse) => format!("{},{},{}", parse.min.as_nanos(), parse.median.as_nanos(), parse.mean.as_nanos()),
+            None => ",,".to_string(),
+        };
+        println!(
+            "{},\"{}\",{},{},{},{},{},{}",
+            result.key,
+            result.name,
+            result.iterations,
+            parse,
+            result.solve.min.as_nanos(),
+            result.solve.median.as_nanos(),
+            result.solve.mean.as_nanos(),
+            result.solve_only().as_nanos(),
+        );
+    }
+}
+
+fn timings_json(timings: Option<Timings>) -> String {
+    match timings {
+        Some(t) => format!("{{\"min_ns\": {}, \"median_ns\": {}, \"mean_ns\": {}}}", t.min.as_nanos(), t.median.as_nanos(), t.mean.as_nanos()),
+        None => "null".to_string(),
+    }
+}
+
+fn print_json(results: &[BenchResult]) {
+    let entries: Vec<String> = results
+        .iter()
+        .map(|result| {
+            format!(
+                "  {{\"key\": \"{}\", \"name\": \"{}\", \"iterations\": {}, \"parse\": {}, \"solve\": {}, \"solve_only_median_ns\": {}}}",
+                result.key,
+                result.name,
+                result.iterations,
+                timings_json(result.parse),
+                timings_json(Some(result.solve)),
+                result.solve_only().as_nanos(),
+            )
+        })
+        .collect();
+
+    println!("[\n{}\n]", entries.join(",\n"));
+}
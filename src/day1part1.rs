@@ -1,73 +1,214 @@
+use std::fmt;
+
+use crate::answer::Answer;
+use crate::day1part2;
+use crate::dial::Dial;
+use crate::error::ParseError;
+use crate::solver::{ReportOptions, Solver};
+
 // We have a dial with numbers 0 to 99. It starts at 50.
 // An L value subtracts that number, an R adds it. After
 // Each rotation, we need to check to see whether the
 // value is 0 and count it, since that will be our answer
 
-/// A representation of both the current position of the dial
-struct DialValue {
-    position: i32,
-    password_count: i32
+// Update: the DialValue that used to live here (and its copy in part 2)
+// is the Dial in dial.rs now, which can be any size and start anywhere.
+// The puzzle's dial is still 0 to 99 starting at 50.
+pub const DIAL_SIZE: i64 = 100;
+pub const DIAL_START: i64 = 50;
+
+/// One line of the input: which way to turn the dial and by how much
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Instruction {
+    Left(u64),
+    Right(u64),
 }
 
-/// Define how to turn right and left on the dial and
-/// keep track of when the value ends up as 0
-impl DialValue {
-
-    // Add the new value to the current position. It can only
-    // go up to 99, but the instructions could give us a 
-    // crazy number like 250. If that were our first input,
-    // we'd have 300 which is off the charts, so we need to subtrct
-    // 99 until we are in range for the dial.
-    fn increment(&mut self, amount: i32) {
-        self.position += amount;
-        while self.position > 99 {
-            self.position -= 100;
+impl Instruction {
+    /// How many clicks to turn the dial, negative for left. An i128 so
+    /// every u64 magnitude fits in either direction.
+    pub fn clicks(self) -> i128 {
+        match self {
+            Instruction::Left(n) => -i128::from(n),
+            Instruction::Right(n) => i128::from(n),
         }
-        if self.position == 0 {
-            self.password_count += 1;
+    }
+
+    /// Parse one line of the input. Whitespace around the instruction (or
+    /// between the direction and the magnitude) is fine, and so is a
+    /// lowercase l or r. Blank lines are None.
+    pub fn parse(line_number: usize, line: &str) -> Result<Option<Instruction>, ParseError> {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            return Ok(None);
         }
+
+        // columns are counted in the line as it was, leading space and all
+        let indent = line.chars().take_while(|c| c.is_whitespace()).count();
+
+        // the direction first, so something like X on its own is blamed on
+        // the X and not on the missing number after it
+        let mut chars = trimmed.chars();
+        let direction = chars.next().unwrap();
+        let turn: fn(u64) -> Instruction = match direction {
+            'L' | 'l' => Instruction::Left,
+            'R' | 'r' => Instruction::Right,
+            _ => return Err(ParseError::new(line_number, indent + 1, &direction.to_string(), "dial direction not L or R")),
+        };
+
+        let rest = chars.as_str();
+        let magnitude = rest.trim_start();
+        let magnitude_column = indent + 2 + (rest.chars().count() - magnitude.chars().count());
+
+        if magnitude.is_empty() {
+            return Err(ParseError::new(line_number, magnitude_column, trimmed, "instruction is missing a magnitude"));
+        }
+        if let Some((offset, c)) = magnitude.chars().enumerate().find(|(_, c)| !c.is_ascii_digit()) {
+            return Err(ParseError::new(line_number, magnitude_column + offset, &c.to_string(), "instruction magnitude must be a whole number"));
+        }
+        let Ok(magnitude) = magnitude.parse::<u64>() else {
+            return Err(ParseError::new(line_number, magnitude_column, magnitude, "instruction magnitude is too big"));
+        };
+
+        Ok(Some(turn(magnitude)))
     }
+}
 
-    // Same idea, but down.
-    fn decrement(&mut self, amount: i32) {
-        self.position -= amount;
-        while self.position < 0 {
-            self.position += 100;   
+/// Written the way it is in the input e.g. L68
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Instruction::Left(n) => write!(f, "L{}", n),
+            Instruction::Right(n) => write!(f, "R{}", n),
         }
-        if self.position == 0 {
-            self.password_count += 1;
+    }
+}
+
+/// Read every instruction in the input, along with the line it was on.
+/// Both parts (and the trace in part 2) use this so they can't disagree
+/// about what the input says.
+///
+/// This used to split on "\n" and grab the first character of each line,
+/// which panicked (or complained) about a trailing newline, CRLF line
+/// endings, a lowercase letter, or a stray space. lines() takes care of the
+/// line endings and Instruction::parse takes care of the rest.
+pub fn parse_instructions(content: &str) -> Result<Vec<(usize, Instruction)>, ParseError> {
+    let mut instructions = vec![];
+
+    for (line_index, line) in content.lines().enumerate() {
+        if let Some(instruction) = Instruction::parse(line_index + 1, line)? {
+            instructions.push((line_index + 1, instruction));
         }
     }
+
+    Ok(instructions)
 }
 
-pub fn solve(content: &str) {
-    let mut dial = DialValue{position:50, password_count: 0};
+/// Add onto a password count, or complain about the instruction that made
+/// it too big. It takes a lot of u64 sized turns to fill up a u128, but
+/// it's better to say so than to quietly wrap around.
+pub fn add_to_count(count: u128, more: u128, line_number: usize, instruction: Instruction) -> Result<u128, ParseError> {
+    count.checked_add(more).ok_or_else(|| {
+        ParseError::new(line_number, 1, &instruction.to_string(), "the password got too big to count")
+    })
+}
 
-    // Iterate over each line of input
-    for line in content.split("\n") {
+pub fn solve(content: &str) -> Result<Answer, ParseError> {
+    let mut dial = Dial::new(DIAL_SIZE, DIAL_START);
+    let mut password_count = 0;
 
-        // Extract the direction and magnitude from each line
-        let Some(direction) = line.get(0..1) else {
-            panic!("Can't parse instruction direction");
-        };
+    for (line_number, instruction) in parse_instructions(content)? {
+        dial.rotate(instruction.clicks());
 
-        let Some(magnitude_string) = line.get(1..) else {
-            panic!("Can't parse instruction magnitude");
-        };
+        // only count the times it stops on 0
+        if dial.is_at(0) {
+            password_count = add_to_count(password_count, 1, line_number, instruction)?;
+        }
+    }
 
-        let Ok(magnitude) = magnitude_string.parse::<i32>() else {
-            panic!("Can't convert instruction magnitude to int");
+    Ok(Answer::Text(format!("{} (final dial position {})", password_count, dial.position())))
+}
+
+pub struct Solution;
+
+impl Solver for Solution {
+    fn day(&self) -> u8 {
+        1
+    }
+
+    fn part(&self) -> u8 {
+        1
+    }
+
+    fn name(&self) -> &'static str {
+        "dial password counting stops at 0"
+    }
+
+    fn parse(&self, content: &str) -> Option<Result<(), ParseError>> {
+        Some(parse_instructions(content).map(|_| ()))
+    }
+
+    fn solve(&self, content: &str) -> Result<Answer, ParseError> {
+        solve(content)
+    }
+
+    fn report(&self, content: &str, options: ReportOptions) -> Option<Result<String, ParseError>> {
+        Some(day1part2::trace(content).map(|steps| day1part2::format_trace(&steps, options.format)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_messy_instructions() {
+        let content = "L68\r\n  r48 \n\nl 5\nR0\r\n";
+        assert_eq!(
+            parse_instructions(content).unwrap(),
+            vec![(1, Instruction::Left(68)), (2, Instruction::Right(48)), (4, Instruction::Left(5)), (5, Instruction::Right(0))]
+        );
+        assert_eq!(parse_instructions("").unwrap(), vec![]);
+    }
+
+    #[test]
+    fn rejects_bad_instructions_with_where_they_are() {
+        let error = |content: &str| {
+            let e = parse_instructions(content).unwrap_err();
+            (e.line, e.column, e.text, e.message)
         };
 
-        // Now that everything is legit, actually update the values
-        // based on the instruction.
-        match direction {
-            "L" => dial.decrement(magnitude),
-            "R" => dial.increment(magnitude),
-            _ => panic!("dial direction not L or R")
+        assert_eq!(error("L1\nX5"), (2, 1, "X".to_string(), "dial direction not L or R".to_string()));
+        assert_eq!(error("X"), (1, 1, "X".to_string(), "dial direction not L or R".to_string()));
+        assert_eq!(error("Xabc"), (1, 1, "X".to_string(), "dial direction not L or R".to_string()));
+        assert_eq!(error("L1\n  Xabc"), (2, 3, "X".to_string(), "dial direction not L or R".to_string()));
+        assert_eq!(error("L1\n  R"), (2, 4, "R".to_string(), "instruction is missing a magnitude".to_string()));
+        assert_eq!(error("L1\nR2\n R-5"), (3, 3, "-".to_string(), "instruction magnitude must be a whole number".to_string()));
+        assert_eq!(error("L 1x"), (1, 4, "x".to_string(), "instruction magnitude must be a whole number".to_string()));
+        assert_eq!(error("R18446744073709551616"), (1, 2, "18446744073709551616".to_string(), "instruction magnitude is too big".to_string()));
+    }
+
+    #[test]
+    fn instructions_round_trip() {
+        for instruction in [Instruction::Left(68), Instruction::Right(0)] {
+            let text = instruction.to_string();
+            assert_eq!(Instruction::parse(1, &text).unwrap(), Some(instruction));
         }
-    };
+        assert_eq!(Instruction::Left(5).clicks(), -5);
+    }
 
-    println!("final dial position: {}", dial.position);
-    println!("count of stops at 0: {}", dial.password_count);
+    #[test]
+    fn huge_magnitudes() {
+        // a few billion clicks used to be too big to parse
+        assert_eq!(solve("L5000000050\nR18446744073709551615").unwrap(), Answer::from("1 (final dial position 15)"));
+        assert_eq!(crate::day1part2::solve("L5000000050").unwrap(), Answer::from("50000001 (final dial position 0)"));
+        assert_eq!(crate::day1part2::solve("R18446744073709551615").unwrap(), Answer::from("184467440737095516 (final dial position 65)"));
+    }
+
+    #[test]
+    fn counts_that_overflow_are_errors() {
+        assert_eq!(add_to_count(5, 6, 1, Instruction::Left(1)), Ok(11));
+        let e = add_to_count(u128::MAX, 1, 7, Instruction::Right(3)).unwrap_err();
+        assert_eq!((e.line, e.text.as_str(), e.message.as_str()), (7, "R3", "the password got too big to count"));
+    }
 }
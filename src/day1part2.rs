@@ -1,3 +1,9 @@
+use crate::answer::Answer;
+use crate::day1part1::{add_to_count, parse_instructions, Instruction, DIAL_SIZE, DIAL_START};
+use crate::dial::Dial;
+use crate::error::ParseError;
+use crate::solver::{OutputFormat, ReportOptions, Solver};
+
 // Same as part 1, but now we need to make a note every time
 // the dial ever pointed at 0, not just if it stopped there
 // after a turn.
@@ -17,83 +23,183 @@
 //     150 then divide by 100 to get the # of times we passed 0
 //     and mod 100 to get the new number.
 
-/// A representation of both the current position of the dial
-struct DialValue {
-    position: i32,
-    password_count: i32
+// Update: I went with more math in the end. Both edge cases fall out of
+// Dial::passes in dial.rs: it counts landing on 0 and never counts the spot
+// the turn started from, whichever way the dial goes.
+
+pub fn solve(content: &str) -> Result<Answer, ParseError> {
+    let mut dial = Dial::new(DIAL_SIZE, DIAL_START);
+    let mut password_count = 0;
+
+    // count every time the dial points at 0 during each turn
+    for (line_number, instruction) in parse_instructions(content)? {
+        let passes = dial.rotate_past(instruction.clicks(), 0);
+        password_count = add_to_count(password_count, passes, line_number, instruction)?;
+    }
+
+    Ok(Answer::Text(format!("{} (final dial position {})", password_count, dial.position())))
 }
 
-/// Define how to turn right and left on the dial and
-/// keep track of when the dial points at 0.
-impl DialValue {
-
-    // Add the new value to the current position. It can only
-    // go up to 99, but the instructions could give us a 
-    // crazy number like 250. If that were our first input,
-    // we'd have 300 which is off the charts, so we need to subtrct
-    // 99 until we are in range for the dial.
-    fn increment(&mut self, amount: i32) {
-        self.position += amount;
-
-        while self.position > 99 {
-            self.position -= 100;
-            self.password_count += 1;
-        }
+// The trace: what happened on every single turn, so when the two parts
+// (or part 2 and a hand count) disagree there's something to look at
+// besides two totals. The running totals for both ways of counting are
+// in there too, so it's easy to spot the line where they drift apart.
+
+/// One turn of the dial
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TraceStep {
+    pub line: usize,
+    pub instruction: Instruction,
+    pub start: i64,
+    pub end: i64,
+    /// how many times the dial pointed at 0 during the turn (part 2)
+    pub crossings: u128,
+    /// whether the turn ended on 0 (part 1)
+    pub stopped_on_zero: bool,
+    /// part 1's password so far
+    pub stops_total: u128,
+    /// part 2's password so far
+    pub crossings_total: u128,
+}
+
+/// Turn the dial through every instruction and record each turn
+pub fn trace(content: &str) -> Result<Vec<TraceStep>, ParseError> {
+    let mut dial = Dial::new(DIAL_SIZE, DIAL_START);
+    let mut steps: Vec<TraceStep> = vec![];
+
+    for (line, instruction) in parse_instructions(content)? {
+        let start = dial.position();
+        let crossings = dial.rotate_past(instruction.clicks(), 0);
+        let stopped_on_zero = dial.is_at(0);
+        let (stops_total, crossings_total) = steps.last().map(|s| (s.stops_total, s.crossings_total)).unwrap_or((0, 0));
+        let stops_total = add_to_count(stops_total, stopped_on_zero as u128, line, instruction)?;
+        let crossings_total = add_to_count(crossings_total, crossings, line, instruction)?;
+
+        steps.push(TraceStep {
+            line,
+            instruction,
+            start,
+            end: dial.position(),
+            crossings,
+            stopped_on_zero,
+            stops_total,
+            crossings_total,
+        });
     }
 
-    // Same idea, but down.
-    // If we turn left and land on 0, make sure we count it.
-    // If you start at 0 then overflow left, we shouldn't count it.
-    fn decrement(&mut self, amount: i32) {
-        // if we are decrementing starting from 0, 
-        // we won't pass it again, but the approach below will
-        // over count by one, so subtract one to keep it even
-        if self.position == 0 {
-            self.password_count -= 1;
-        }
-        
-        self.position -= amount;
+    Ok(steps)
+}
 
-        // each time we've over shot 0, count it
-        while self.position < 0 {
-            self.position += 100;  
-            self.password_count += 1;
-        }
+/// Show a trace as a table, csv, or json
+pub fn format_trace(steps: &[TraceStep], format: OutputFormat) -> String {
+    let turn = |step: &TraceStep| step.instruction.to_string();
 
-        // if the new dial position is 0
-        if self.position == 0 {
-            self.password_count += 1;
+    match format {
+        OutputFormat::Table => {
+            let mut lines = vec![format!("{:>6} {:>12} {:>5} {:>5} {:>9} {:>7} {:>8} {:>8}", "line", "turn", "start", "end", "crossings", "stopped", "part 1", "part 2")];
+            for step in steps {
+                lines.push(format!(
+                    "{:>6} {:>12} {:>5} {:>5} {:>9} {:>7} {:>8} {:>8}",
+                    step.line,
+                    turn(step),
+                    step.start,
+                    step.end,
+                    step.crossings,
+                    if step.stopped_on_zero { "yes" } else { "no" },
+                    step.stops_total,
+                    step.crossings_total,
+                ));
+            }
+            lines.join("\n")
+        }
+        OutputFormat::Csv => {
+            let mut lines = vec!["line,turn,start,end,crossings,stopped_on_zero,part1_total,part2_total".to_string()];
+            for step in steps {
+                lines.push(format!(
+                    "{},{},{},{},{},{},{},{}",
+                    step.line, turn(step), step.start, step.end, step.crossings, step.stopped_on_zero, step.stops_total, step.crossings_total
+                ));
+            }
+            lines.join("\n")
+        }
+        OutputFormat::Json => {
+            let entries: Vec<String> = steps
+                .iter()
+                .map(|step| {
+                    format!(
+                        "  {{\"line\": {}, \"turn\": \"{}\", \"start\": {}, \"end\": {}, \"crossings\": {}, \"stopped_on_zero\": {}, \"part1_total\": {}, \"part2_total\": {}}}",
+                        step.line, turn(step), step.start, step.end, step.crossings, step.stopped_on_zero, step.stops_total, step.crossings_total
+                    )
+                })
+                .collect();
+            format!("[\n{}\n]", entries.join(",\n"))
         }
     }
 }
 
-pub fn solve(content: &str) {
-    let mut dial = DialValue{position:50, password_count: 0};
-
-    // Iterate over each line of input
-    for line in content.split("\n") {
-        // Extract the direction and magnitude from each line
-        let Some(direction) = line.get(0..1) else {
-            panic!("Can't parse instruction direction");
-        };
-
-        let Some(magnitude_string) = line.get(1..) else {
-            panic!("Can't parse instruction magnitude");
-        };
-
-        let Ok(magnitude) = magnitude_string.parse::<i32>() else {
-            panic!("Can't convert instruction magnitude to int");
-        };
-
-        // Now that everything is legit, actually update the values
-        // based on the instruction.
-        match direction {
-            "L" => dial.decrement(magnitude),
-            "R" => dial.increment(magnitude),
-            _ => panic!("dial direction not L or R")
-        }
-    };
+pub struct Solution;
+
+impl Solver for Solution {
+    fn day(&self) -> u8 {
+        1
+    }
 
-    println!("final dial position: {}", dial.position);
-    println!("count of clicks at 0: {}", dial.password_count);
-}
\ No newline at end of file
+    fn part(&self) -> u8 {
+        2
+    }
+
+    fn name(&self) -> &'static str {
+        "dial password counting clicks at 0"
+    }
+
+    fn parse(&self, content: &str) -> Option<Result<(), ParseError>> {
+        Some(parse_instructions(content).map(|_| ()))
+    }
+
+    fn solve(&self, content: &str) -> Result<Answer, ParseError> {
+        solve(content)
+    }
+
+    fn report(&self, content: &str, options: ReportOptions) -> Option<Result<String, ParseError>> {
+        Some(trace(content).map(|steps| format_trace(&steps, options.format)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trace_the_edge_cases() {
+        // R50 lands on 0, L5 leaves 0 without crossing it, L95 lands on it
+        // again, and R250 goes past 0 twice before stopping on 50. Part 1
+        // only sees the two stops.
+        let steps = trace("R50\nL5\nL95\nR250").unwrap();
+        let summary: Vec<(i64, i64, u128, bool)> = steps.iter().map(|s| (s.start, s.end, s.crossings, s.stopped_on_zero)).collect();
+        assert_eq!(summary, vec![(50, 0, 1, true), (0, 95, 0, false), (95, 0, 1, true), (0, 50, 2, false)]);
+        assert_eq!(steps.last().map(|s| (s.stops_total, s.crossings_total)), Some((2, 4)));
+    }
+
+    #[test]
+    fn trace_matches_both_parts() {
+        let content = "L68\nL30\nR48\nL5\nR60\nL55\nL1\nL99\nR14\nL82";
+        let last = *trace(content).unwrap().last().unwrap();
+        assert_eq!(crate::day1part1::solve(content).unwrap(), Answer::from(format!("{} (final dial position {})", last.stops_total, last.end)));
+        assert_eq!(solve(content).unwrap(), Answer::from(format!("{} (final dial position {})", last.crossings_total, last.end)));
+    }
+
+    #[test]
+    fn trace_formats() {
+        let steps = trace("L68\n\nR18\n").unwrap();
+        assert_eq!(
+            format_trace(&steps, OutputFormat::Csv),
+            "line,turn,start,end,crossings,stopped_on_zero,part1_total,part2_total\n1,L68,50,82,1,false,0,1\n3,R18,82,0,1,true,1,2"
+        );
+        assert_eq!(
+            format_trace(&steps[..1], OutputFormat::Table),
+            "  line         turn start   end crossings stopped   part 1   part 2\n     1          L68    50    82         1      no        0        1"
+        );
+        assert!(format_trace(&steps, OutputFormat::Json).starts_with("[\n  {\"line\": 1, \"turn\": \"L68\","));
+        assert!(trace("L68\nX5").is_err());
+    }
+}
@@ -0,0 +1,232 @@
+use crate::day1part1::{add_to_count, Instruction, DIAL_SIZE};
+use crate::error::ParseError;
+use crate::solver::OutputFormat;
+
+// Going the other way on day 1: instead of starting at 50 and working out
+// the password, work out the password for every place the dial could have
+// started and see which ones give the password we're after. Handy for
+// making up new versions of the puzzle and checking they only have one
+// answer.
+//
+// Running the whole thing 100 times would work, but it can be done in one
+// trip through the instructions. Wherever the dial started, after the same
+// turns it has moved by the same amount, so dial s is always pointing at
+// (s + offset) where offset is how far the turns so far add up to.
+//
+// Part 1: after each turn exactly one start is sitting on 0, the one with
+// s + offset = 0, so that start gets one more stop.
+//
+// Part 2: a turn of c clicks is c / 100 whole laps, which pass 0 once each
+// no matter where the dial is, plus r = c % 100 more clicks. Those extra
+// clicks only pass 0 from some positions: turning right from 100 - r up to
+// 99, and turning left from 1 up to r. That's a run of positions, which is
+// a run of starts (wrapped around the dial), so it goes in a difference
+// array: +1 where the run starts and -1 after it ends, and adding up the
+// array at the very end gives every start's total.
+
+/// Both passwords for one starting position
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StartPasswords {
+    pub start: i64,
+    /// part 1's password, times it stopped on 0
+    pub stops: u128,
+    /// part 2's password, times it pointed at 0 at all
+    pub passes: u128,
+    /// where the dial ends up
+    pub end: i64,
+}
+
+/// Add 1 to len starts in a row in the difference array, beginning at
+/// first and wrapping around the end of the dial
+fn add_run(diff: &mut [i64], first: i64, len: i64) {
+    let modulus = diff.len() as i64 - 1;
+    let last = first + len;
+
+    diff[first as usize] += 1;
+    if last <= modulus {
+        diff[last as usize] -= 1;
+    } else {
+        diff[modulus as usize] -= 1;
+        diff[0] += 1;
+        diff[(last - modulus) as usize] -= 1;
+    }
+}
+
+/// Both passwords for every starting position on a dial of the given size,
+/// in one pass over the instructions
+pub fn every_start(instructions: &[(usize, Instruction)], modulus: i64) -> Result<Vec<StartPasswords>, ParseError> {
+    assert!(modulus > 0, "a dial needs at least one number on it, not {}", modulus);
+    let m = i128::from(modulus);
+
+    let mut offset: i128 = 0;
+    let mut laps: u128 = 0;
+    let mut stops = vec![0u128; modulus as usize];
+    let mut diff = vec![0i64; modulus as usize + 1];
+
+    for &(line_number, instruction) in instructions {
+        let clicks = instruction.clicks();
+        let extra = (clicks.unsigned_abs() % m as u128) as i64;
+        laps = add_to_count(laps, clicks.unsigned_abs() / m as u128, line_number, instruction)?;
+
+        // the positions that get an extra pass, as starts. Position p
+        // belongs to start p - offset.
+        if extra > 0 {
+            let first_position = if clicks > 0 { modulus - extra } else { 1 };
+            add_run(&mut diff, (i128::from(first_position) - offset).rem_euclid(m) as i64, extra);
+        }
+
+        offset = (offset + clicks).rem_euclid(m);
+
+        // the one start that's stopped on 0 now
+        stops[(-offset).rem_euclid(m) as usize] += 1;
+    }
+
+    let mut extras = 0;
+    let mut results = vec![];
+    for start in 0..modulus {
+        extras += diff[start as usize];
+
+        // laps was checked on the way, so this is the only add left that
+        // could go over. It's blamed on the last instruction since that's
+        // where the count ends up.
+        let passes = match instructions.last() {
+            Some(&(line_number, instruction)) => add_to_count(laps, extras as u128, line_number, instruction)?,
+            None => 0,
+        };
+
+        results.push(StartPasswords {
+            start,
+            stops: stops[start as usize],
+            passes,
+            end: (i128::from(start) + offset).rem_euclid(m) as i64,
+        });
+    }
+
+    Ok(results)
+}
+
+/// every_start on the puzzle's 0-99 dial
+pub fn every_puzzle_start(instructions: &[(usize, Instruction)]) -> Result<Vec<StartPasswords>, ParseError> {
+    every_start(instructions, DIAL_SIZE)
+}
+
+/// The starts that give target as part 1's password, and the ones that
+/// give it as part 2's
+pub fn matching_starts(results: &[StartPasswords], target: u128) -> (Vec<i64>, Vec<i64>) {
+    let part1 = results.iter().filter(|r| r.stops == target).map(|r| r.start).collect();
+    let part2 = results.iter().filter(|r| r.passes == target).map(|r| r.start).collect();
+    (part1, part2)
+}
+
+/// Show every start as a table, csv, or json. With a target, only the
+/// starts that match it (for either part) are shown, followed by a summary
+/// in the table.
+pub fn format_starts(results: &[StartPasswords], target: Option<u128>, format: OutputFormat) -> String {
+    let matches = |r: &&StartPasswords| target.is_none_or(|t| r.stops == t || r.passes == t);
+    let shown: Vec<&StartPasswords> = results.iter().filter(matches).collect();
+
+    match format {
+        OutputFormat::Table => {
+            let mut lines = vec![format!("{:>5} {:>20} {:>20} {:>5}", "start", "part 1", "part 2", "end")];
+            for r in &shown {
+                lines.push(format!("{:>5} {:>20} {:>20} {:>5}", r.start, r.stops, r.passes, r.end));
+            }
+            if let Some(target) = target {
+                let (part1, part2) = matching_starts(results, target);
+                let list = |starts: Vec<i64>| if starts.is_empty() { "none".to_string() } else { starts.iter().map(|s| s.to_string()).collect::<Vec<String>>().join(", ") };
+                lines.push(format!("part 1 password {} from starts: {}", target, list(part1)));
+                lines.push(format!("part 2 password {} from starts: {}", target, list(part2)));
+            }
+            lines.join("\n")
+        }
+        OutputFormat::Csv => {
+            let mut lines = vec!["start,part1,part2,end".to_string()];
+            for r in &shown {
+                lines.push(format!("{},{},{},{}", r.start, r.stops, r.passes, r.end));
+            }
+            lines.join("\n")
+        }
+        OutputFormat::Json => {
+            let entries: Vec<String> = shown
+                .iter()
+                .map(|r| format!("  {{\"start\": {}, \"part1\": {}, \"part2\": {}, \"end\": {}}}", r.start, r.stops, r.passes, r.end))
+                .collect();
+            format!("[\n{}\n]", entries.join(",\n"))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day1part1::parse_instructions;
+    use crate::dial::Dial;
+    use crate::rng::cases;
+
+    // Just running every start one at a time, for checking every_start
+    fn one_at_a_time(instructions: &[(usize, Instruction)], modulus: i64) -> Vec<StartPasswords> {
+        (0..modulus)
+            .map(|start| {
+                let mut dial = Dial::new(modulus, start);
+                let mut stops = 0;
+                let mut passes = 0;
+                for (_, instruction) in instructions {
+                    passes += dial.rotate_past(instruction.clicks(), 0);
+                    stops += dial.is_at(0) as u128;
+                }
+                StartPasswords { start, stops, passes, end: dial.position() }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn the_example_from_50() {
+        let instructions = parse_instructions("L68\nL30\nR48\nL5\nR60\nL55\nL1\nL99\nR14\nL82").unwrap();
+        let results = every_puzzle_start(&instructions).unwrap();
+        assert_eq!(results[50], StartPasswords { start: 50, stops: 3, passes: 6, end: 32 });
+        assert_eq!(results, one_at_a_time(&instructions, DIAL_SIZE));
+
+        let (part1, part2) = matching_starts(&results, 3);
+        assert!(part1.contains(&50));
+        assert!(!part2.contains(&50));
+    }
+
+    #[test]
+    fn add_run_wraps_around() {
+        // starts 3, 4, 0, and 1 on a dial of 5
+        let mut diff = vec![0; 6];
+        add_run(&mut diff, 3, 4);
+        assert_eq!(diff, vec![1, 0, -1, 1, 0, -1]);
+
+        let mut diff = vec![0; 6];
+        add_run(&mut diff, 1, 4);
+        assert_eq!(diff, vec![0, 1, 0, 0, 0, -1]);
+    }
+
+    // lots of random instructions on lots of dial sizes, one pass has to
+    // agree with running each start on its own
+    #[test]
+    fn matches_one_at_a_time() {
+        cases(0x1f83d9abfb41bd6b, 200, |rng| {
+            let modulus = rng.below(30) as i64 + 1;
+            let instructions: Vec<(usize, Instruction)> = (0..rng.below(20) as usize)
+                .map(|i| {
+                    let n = rng.below(300);
+                    (i + 1, if rng.coin() { Instruction::Left(n) } else { Instruction::Right(n) })
+                })
+                .collect();
+
+            assert_eq!(every_start(&instructions, modulus).unwrap(), one_at_a_time(&instructions, modulus), "{:?} on {}", instructions, modulus);
+        });
+    }
+
+    #[test]
+    fn format_only_shows_matches() {
+        let instructions = parse_instructions("R50\nL1").unwrap();
+        let results = every_puzzle_start(&instructions).unwrap();
+        let csv = format_starts(&results, Some(2), OutputFormat::Csv);
+        // only starting on 51 passes 0 twice, once on the way round to it
+        // with R50 and again by stopping on it after L1
+        assert_eq!(csv, "start,part1,part2,end\n51,1,2,0");
+    }
+}
@@ -0,0 +1,191 @@
+use crate::answer::Answer;
+use crate::day2part1::{check_number, parse_ranges};
+use crate::day2part2::{self, generate_all_numbers, number_ranges_by_length, range_reports, series_totals, Repeats, Totals};
+use crate::error::ParseError;
+use crate::rng::Rng;
+
+// Part 1 checks every single number, which is slow but hard to get wrong.
+// Part 2 generates the invalid numbers (or adds them up with inclusion-
+// exclusion, or with arithmetic series) which is fast but has a lot more
+// places for an off by one to hide. This runs the clever ways and the
+// brute force way over the same ranges and complains about any range
+// where they don't agree.
+//
+// Part 1 never had a brute force version of the "any repeated block"
+// rule, so there's one here. It's deliberately dumb and doesn't share
+// anything with the generator.
+
+/// Brute force check for part 2's rule: is num some block repeated at
+/// least twice? Tries every block size that could possibly work.
+pub fn check_any_repeat(num: &str) -> bool {
+    let len = num.len();
+    (1..=len / 2).any(|n| len.is_multiple_of(n) && num[..n].repeat(len / n) == num)
+}
+
+/// Count and add up the invalid numbers in start..=end one at a time
+pub fn brute_force(start: u128, end: u128, repeats: Repeats) -> Totals {
+    let check = match repeats {
+        Repeats::Twice => check_number,
+        Repeats::AnyNumber => check_any_repeat,
+    };
+
+    let mut totals = Totals { count: 0, sum: 0 };
+    for n in (start..=end).filter(|n| check(&n.to_string())) {
+        totals.count += 1;
+        totals.sum += n;
+    }
+    totals
+}
+
+/// A range where one of the clever ways didn't get the same thing as
+/// checking every number
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Disagreement {
+    pub start: u128,
+    pub end: u128,
+    pub repeats: Repeats,
+    /// which of the clever ways got it wrong e.g. "generator"
+    pub strategy: &'static str,
+    pub brute_force: Totals,
+    pub found: Totals,
+}
+
+/// Sums that come back as an Answer (from solve) turned back into a u128.
+/// Anything else (like an error) can't match, so it's None.
+fn answer_sum(answer: Result<Answer, ParseError>) -> Option<u128> {
+    match answer {
+        Ok(Answer::Int(n)) => u128::try_from(n).ok(),
+        Ok(Answer::Text(text)) => text.parse().ok(),
+        Err(_) => None,
+    }
+}
+
+/// Run every way we have of finding the invalid numbers in start..=end,
+/// for both rules, and hand back everywhere they disagree with brute force
+pub fn check_range(start: u128, end: u128) -> Vec<Disagreement> {
+    let content = format!("{}-{}", start, end);
+    let mut disagreements = vec![];
+
+    for repeats in [Repeats::Twice, Repeats::AnyNumber] {
+        let expected = brute_force(start, end, repeats);
+        let mut found = vec![];
+
+        // a parse error or an overflow shows up as a count and sum of 0,
+        // which is always wrong because brute force never errors
+        let broken = Totals { count: 0, sum: 0 };
+        found.push(("series", series_totals(&content, repeats).unwrap_or(broken)));
+
+        // the --report listing has its own way of finding the IDs too
+        let report = range_reports(&content, repeats, None).ok().and_then(|reports| reports.into_iter().next());
+        found.push(("report", report.map(|r| Totals { count: r.count, sum: r.ids.iter().map(|(id, _)| id).sum() }).unwrap_or(broken)));
+
+        if repeats == Repeats::AnyNumber {
+            let generated: Vec<u128> = number_ranges_by_length(&start.to_string(), &end.to_string())
+                .iter()
+                .flat_map(|(low, high)| generate_all_numbers(low, high))
+                .collect();
+            found.push(("generator", Totals { count: generated.len() as u128, sum: generated.iter().sum() }));
+
+            // solve only gives the sum, so it gets brute force's count
+            let sum = answer_sum(day2part2::solve(&content));
+            found.push(("inclusion-exclusion", Totals { count: expected.count, sum: sum.unwrap_or(0) }));
+        }
+
+        for (strategy, totals) in found {
+            if totals != expected {
+                disagreements.push(Disagreement { start, end, repeats, strategy, brute_force: expected, found: totals });
+            }
+        }
+    }
+
+    disagreements
+}
+
+/// Check every range in a day 2 input. Brute force has to look at every
+/// number, so this is only as quick as part 1 is.
+pub fn check_input(content: &str) -> Result<Vec<Disagreement>, ParseError> {
+    let mut disagreements = vec![];
+
+    for (start, end) in parse_ranges(content)? {
+        disagreements.extend(check_range(start.parse().unwrap(), end.parse().unwrap()));
+    }
+
+    Ok(disagreements)
+}
+
+/// A bunch of random ranges to check. They're kept to a few thousand
+/// numbers each so brute force can keep up, and some of them start just
+/// under a power of 10 so the ranges that change length get tried too.
+pub fn random_ranges(seed: u64, count: usize) -> Vec<(u128, u128)> {
+    let mut rng = Rng::new(seed);
+
+    (0..count)
+        .map(|_| {
+            let length = rng.below(15) as u32 + 1;
+            let start = if rng.next_u64().is_multiple_of(4) {
+                u128::pow(10, length).saturating_sub(u128::from(rng.below(2000))).max(1)
+            } else {
+                u128::from(rng.next_u64()) % u128::pow(10, length) + 1
+            };
+            (start, start + u128::from(rng.below(5000)))
+        })
+        .collect()
+}
+
+/// Check a bunch of random ranges, see random_ranges
+pub fn check_random(seed: u64, count: usize) -> Vec<Disagreement> {
+    random_ranges(seed, count).into_iter().flat_map(|(start, end)| check_range(start, end)).collect()
+}
+
+/// Print the disagreements (if there are any), one per line
+pub fn print_disagreements(disagreements: &[Disagreement], ranges_checked: usize) {
+    for d in disagreements {
+        let rule = match d.repeats {
+            Repeats::Twice => "repeated twice",
+            Repeats::AnyNumber => "any repeated block",
+        };
+        println!(
+            "{}-{} ({}): brute force found {} IDs adding up to {}, {} found {} adding up to {}",
+            d.start, d.end, rule, d.brute_force.count, d.brute_force.sum, d.strategy, d.found.count, d.found.sum
+        );
+    }
+
+    println!("{} ranges checked, {} disagreements", ranges_checked, disagreements.len());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_any_repeat_finds_every_block_size() {
+        assert!(check_any_repeat("11"));
+        assert!(check_any_repeat("121212"));
+        assert!(check_any_repeat("123123"));
+        assert!(check_any_repeat("1111111"));
+        assert!(!check_any_repeat("7"));
+        assert!(!check_any_repeat("1231234"));
+        assert!(!check_any_repeat("1212121"));
+    }
+
+    #[test]
+    fn brute_force_on_the_example_ranges() {
+        assert_eq!(brute_force(95, 115, Repeats::Twice), Totals { count: 1, sum: 99 });
+        assert_eq!(brute_force(95, 115, Repeats::AnyNumber), Totals { count: 2, sum: 99 + 111 });
+    }
+
+    #[test]
+    fn no_disagreements_on_the_example() {
+        let content = "11-22,95-115,998-1012,1188511880-1188511890,222220-222224,1698522-1698528,446443-446449,38593856-38593862";
+        assert_eq!(check_input(content).unwrap(), vec![]);
+        assert!(check_input("11-22,oops").is_err());
+    }
+
+    #[test]
+    fn no_disagreements_on_random_ranges() {
+        let ranges = random_ranges(0x5eed, 100);
+        assert_eq!(ranges.len(), 100);
+        assert!(ranges.iter().all(|(start, end)| start <= end));
+        assert_eq!(check_random(0x5eed, 100), vec![]);
+    }
+}
@@ -1,3 +1,8 @@
+use crate::answer::Answer;
+use crate::day2part2::{self, series_totals, Repeats};
+use crate::error::ParseError;
+use crate::solver::{ReportOptions, Solver};
+
 // Part 2
 // Similar to 1 in that we are given a range of numbers and need
 
@@ -10,15 +15,15 @@
 
 /// checks whether a number is "invalid" meaning it is even and the
 /// first half of the number matches the second half of the number
-fn check_number(num: &str) -> bool {
+pub fn check_number(num: &str) -> bool {
     let num_len = num.len();
 
     // skip odd numbered ones since those can't be repeated sequences
-    return num_len % 2 == 0 && num[0..num_len/2] == num[num_len/2..]
+    num_len.is_multiple_of(2) && num[0..num_len/2] == num[num_len/2..]
 }
 
-/// My original solution, before I tried to make things a little more Rusty
-// pub fn solve(content: &str) {
+// My original solution, before I tried to make things a little more Rusty
+// pub fn solve(content: &str) -> Result<Answer, ParseError> {
 //     let mut running_sum: u64 = 0;
 //     // split the numbers
 //     for s in content.split(',') {
@@ -37,22 +42,156 @@ fn check_number(num: &str) -> bool {
 //     println!("final count: {}", running_sum);
 // }
 
-pub fn solve(content: &str) {
-    let total: u64 = content
-        .split(',')
-        .flat_map(|range_string| {
-            let (start, end) = range_string
-                .split_once('-')
-                .expect("Format should be start-end");
+/// Split the input into its start-end ranges and make sure each one is
+/// actually a pair of numbers with the start no bigger than the end.
+/// The numbers are handed back as strings because part 2 likes working
+/// with the digits directly. Anything up to a u128 is allowed.
+///
+/// Whitespace around the whole input and around each range is ignored,
+/// and so is one comma at the very end, so a file saved with a final
+/// newline (or input piped in with echo) still works.
+pub fn parse_ranges(content: &str) -> Result<Vec<(&str, &str)>, ParseError> {
+    let mut ranges = vec![];
+
+    let trimmed = content.trim();
+    let trimmed = trimmed.strip_suffix(',').unwrap_or(trimmed);
+
+    for range_string in trimmed.split(',').map(str::trim) {
+        // the pieces all borrow from content, so errors can point at the
+        // right spot even after trimming
+        let offset = offset_in(content, range_string);
+
+        let Some((start, end)) = range_string.split_once('-') else {
+            return Err(ParseError::at_offset(content, offset, range_string, "range should be in the format start-end"));
+        };
+
+        let end_offset = offset + start.len() + 1;
+        let mut numbers = vec![];
 
-            let start: u64 = start.parse().unwrap();
-            let end: u64 = end.parse().unwrap();
+        for (number, number_offset) in [(start, offset), (end, end_offset)] {
+            if number.is_empty() || !number.chars().all(|c| c.is_ascii_digit()) {
+                return Err(ParseError::at_offset(content, number_offset, number, "range bounds must be whole numbers"));
+            }
+
+            let Ok(value) = number.parse::<u128>() else {
+                return Err(ParseError::at_offset(content, number_offset, number, "range bound is too big"));
+            };
+            numbers.push(value);
+        }
+
+        if numbers[0] > numbers[1] {
+            return Err(ParseError::at_offset(content, offset, range_string, "range start is after its end"));
+        }
+
+        ranges.push((start, end));
+    }
+
+    Ok(ranges)
+}
+
+/// Where a range (or anything else borrowed from it) starts in the input,
+/// for errors found after parse_ranges has handed the pieces back
+pub fn offset_in(content: &str, piece: &str) -> usize {
+    piece.as_ptr() as usize - content.as_ptr() as usize
+}
+
+pub fn solve(content: &str) -> Result<Answer, ParseError> {
+    let total: u128 = parse_ranges(content)?
+        .into_iter()
+        .flat_map(|(start, end)| {
+            // parse_ranges already made sure these are numbers
+            let start: u128 = start.parse().unwrap();
+            let end: u128 = end.parse().unwrap();
 
             start..=end 
         })
         .filter(|&n| check_number(&n.to_string()))
         .sum();
 
-    println!("final count: {}", total);
+    Ok(total.into())
+}
+
+
+pub struct Solution;
+
+impl Solver for Solution {
+    fn day(&self) -> u8 {
+        2
+    }
+
+    fn part(&self) -> u8 {
+        1
+    }
+
+    fn name(&self) -> &'static str {
+        "sum of IDs repeated twice"
+    }
+
+    fn parse(&self, content: &str) -> Option<Result<(), ParseError>> {
+        Some(parse_ranges(content).map(|_| ()))
+    }
+
+    fn solve(&self, content: &str) -> Result<Answer, ParseError> {
+        solve(content)
+    }
+
+    fn report(&self, content: &str, options: ReportOptions) -> Option<Result<String, ParseError>> {
+        Some(day2part2::report(content, Repeats::Twice, options))
+    }
 }
 
+/// Part 1 with arithmetic series (from part 2) instead of checking every
+/// number, so huge ranges are instant
+pub struct SeriesSolution;
+
+impl Solver for SeriesSolution {
+    fn day(&self) -> u8 {
+        2
+    }
+
+    fn part(&self) -> u8 {
+        1
+    }
+
+    fn name(&self) -> &'static str {
+        "sum of IDs repeated twice with series"
+    }
+
+    fn variant(&self) -> Option<&'static str> {
+        Some("series")
+    }
+
+    fn parse(&self, content: &str) -> Option<Result<(), ParseError>> {
+        Some(parse_ranges(content).map(|_| ()))
+    }
+
+    fn solve(&self, content: &str) -> Result<Answer, ParseError> {
+        Ok(series_totals(content, Repeats::Twice)?.sum.into())
+    }
+
+    fn report(&self, content: &str, options: ReportOptions) -> Option<Result<String, ParseError>> {
+        Some(day2part2::report(content, Repeats::Twice, options))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_ranges_ignores_surrounding_whitespace() {
+        let expected = vec![("11", "22"), ("95", "115")];
+        assert_eq!(parse_ranges("11-22,95-115\n").unwrap(), expected);
+        assert_eq!(parse_ranges("11-22, 95-115, \n").unwrap(), expected);
+        assert_eq!(parse_ranges("  11-22,95-115\r\n").unwrap(), expected);
+    }
+
+    #[test]
+    fn parse_ranges_errors_still_point_at_the_input() {
+        let e = parse_ranges("11-22, 95-x\n").unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (1, 11, "x"));
+        assert!(parse_ranges("11-22,,95-115").is_err());
+        assert!(parse_ranges("\n").is_err());
+        assert!(parse_ranges("22-11").is_err());
+    }
+}
@@ -1,4 +1,7 @@
-use std::collections::HashSet;
+use crate::answer::Answer;
+use crate::day2part1::{offset_in, parse_ranges};
+use crate::error::ParseError;
+use crate::solver::{OutputFormat, ReportOptions, Solver};
 
 // Part 2
 // Similar to 1 in that we are given a range of numbers and need
@@ -64,6 +67,26 @@ use std::collections::HashSet;
 
 
 
+// Update: that expanded form only went up to 10 digits, and anything
+// longer printed a complaint and got skipped. The block sizes for a length
+// are just its divisors (not counting the length itself), so those get
+// worked out now instead of being written down.
+//
+// The HashSet that kept 11111111 from being counted for the 1, 2, and 4
+// digit blocks is gone too. When adding things up, it's inclusion-exclusion
+// over the divisors: the numbers made of 2 digit blocks and the ones made
+// of 4 digit blocks overlap in exactly the ones made of gcd(2, 4) = 2 digit
+// blocks, etc. That works out to adding or subtracting the sum for each
+// block size n depending on the Mobius function of length / n:
+//   8 digits: + (4 digit blocks)                  (2 and 1 are inside 4)
+//   6 digits: + (3 digit blocks) + (2 digit blocks) - (1 digit blocks)
+//  12 digits: + (6 digit blocks) + (4 digit blocks) - (2 digit blocks)
+// When we actually want the list of numbers, each one only gets generated
+// for its smallest block size, so there's nothing to dedup there either.
+//
+// Everything is a u128 now too, so IDs can be up to 39 digits long.
+
+
 /// Returns a vec of number ranges with start and end of the same length
 /// It was easier for me to think through the solution with ranges
 /// of numbers of equal length (e.g. 123-456 (3 digits), 12345-56789 
@@ -71,31 +94,22 @@ use std::collections::HashSet;
 /// problem's input range are of different length (e.g. 1-234, 134-23456). 
 /// It returns all ranges of the same length e.g. 4-234 returns 
 //  (4,9), (10,99), (100,234).
-fn number_ranges_by_length(start: &str, end: &str) -> Vec<(String,String)> {
+pub fn number_ranges_by_length(start: &str, end: &str) -> Vec<(String,String)> {
 
-    // First, build a vec of tuples for the ranges in each
-    // power of 10. E.g. for the range 4-234, this would
-    // yield [(1,9), (10,99), (100,999)]
+    // Each length from the start's to the end's gets a range running from
+    // 100...0 to 999...9, except the first one starts at the start argument
+    // and the last one stops at the end argument. E.g. for the range 4-234
+    // that's [(4,9), (10,99), (100,234)]. Building the 999...9 as a string
+    // means we never need 10^39, which doesn't fit in a u128.
     let mut result = vec![];
-    let start_len = start.len();
-    let end_len = end.len();
 
-    for n in start_len..=end_len {
-        let min = u64::pow(10, (n - 1) as u32);
-        let max = u64::pow(10, n as u32) - 1;
-        result.push((min.to_string(), max.to_string()));
+    for n in start.len()..=end.len() {
+        let low = if n == start.len() { start.to_string() } else { u128::pow(10, (n - 1) as u32).to_string() };
+        let high = if n == end.len() { end.to_string() } else { "9".repeat(n) };
+        result.push((low, high));
     }
 
-    // then update the first item in the first tuple to be the 
-    // start argument e.g. yielding [(4,9), (10,99), (100,999)]
-    let result_len = result.len();
-    result[0] = (start.to_string(), result[0].1.clone());
-
-    // and last item of the last tuple with the end argument
-    // e.g. yielding [(4,9), (10,99), (100,234)]
-    result[result_len - 1] = (result[result_len - 1].0.clone(), end.to_string());
-
-    return result
+    result
 }
 
 
@@ -106,7 +120,7 @@ fn number_ranges_by_length(start: &str, end: &str) -> Vec<(String,String)> {
 /// n=3 look like 123123.
 /// It works by first finding the smallest repeatable number of size n, then the highest.
 /// Then it generates all repeatable numbers between those two.
-fn generate_n_repeated_numbers(start: &str, end: &str, n: usize) -> Vec<String> {
+fn generate_n_repeated_numbers(start: &str, end: &str, n: usize) -> Vec<u128> {
     let number_length = start.len();
 
     // First find the lowest pattern (aka "invalid") number we can make
@@ -123,9 +137,9 @@ fn generate_n_repeated_numbers(start: &str, end: &str, n: usize) -> Vec<String>
     // digits are 12, if we repeated that we'd get 121212. But since our lower bound is
     // 123456, we can't actually use that 121212 because it is less than the lower bound.
     // The first number we'd be able to repeat with that lower bound is 13 giving 131313.
-    let mut smallest_repeatable_number: u64 = start_n_digit_pairs[0].parse().unwrap();
+    let mut smallest_repeatable_number: u128 = start_n_digit_pairs[0].parse().unwrap();
     for c in start_n_digit_pairs {
-        let i: u64 = c.parse().unwrap();
+        let i: u128 = c.parse().unwrap();
 
         if i < smallest_repeatable_number {
             break;
@@ -150,9 +164,9 @@ fn generate_n_repeated_numbers(start: &str, end: &str, n: usize) -> Vec<String>
     // the type of pattern we are repeating. E.g. with an end of 456789 and an n of 2
     // the highest 2 digit repeating pattern is 454545 (as the next highest of 464646)
     // would be out of range.
-    let mut highest_repeatable_number: u64 = end_n_digit_pairs[0].parse().unwrap();
+    let mut highest_repeatable_number: u128 = end_n_digit_pairs[0].parse().unwrap();
     for c in end_n_digit_pairs {
-        let i: u64 = c.parse().unwrap();
+        let i: u128 = c.parse().unwrap();
 
         // if the current number is bigger than the last number we checked, stop.
         // In the 456789 example, we have 45, 67, 89. 67 is greater than 45 
@@ -181,103 +195,763 @@ fn generate_n_repeated_numbers(start: &str, end: &str, n: usize) -> Vec<String>
     // E.g. if we are making numbers of length 4 and n of 2 where the smallest
     // repeatble number is 14 and the highest is 17, we will make the following:
     // 1414, 1515, 1616, and 1717
-    let mut result = Vec::<String>::new();
+    let mut result = Vec::<u128>::new();
     for val in smallest_repeatable_number..=highest_repeatable_number {
-        result.push(val.to_string().repeat(number_length / n));
+        result.push(val.to_string().repeat(number_length / n).parse().unwrap());
     }
 
     result
 }
 
+/// The block sizes a number of this length could be made of, which are
+/// the divisors of the length except the length itself. E.g. 6 -> 1, 2, 3
+/// and 7 -> 1.
+fn block_sizes(length: usize) -> Vec<usize> {
+    (1..length).filter(|n| length.is_multiple_of(*n)).collect()
+}
+
+/// The Mobius function: 0 if n has a squared prime factor, otherwise 1 or
+/// -1 for an even or odd number of prime factors. It's what gives each
+/// block size its sign in the inclusion-exclusion.
+fn mobius(mut n: usize) -> i32 {
+    let mut result = 1;
+    let mut p = 2;
+
+    while p * p <= n {
+        if n.is_multiple_of(p) {
+            n /= p;
+            if n.is_multiple_of(p) {
+                return 0;
+            }
+            result = -result;
+        }
+        p += 1;
+    }
+
+    if n > 1 {
+        result = -result;
+    }
+    result
+}
+
+/// The size of the smallest block that repeats to make num, which is the
+/// length of num itself when it isn't a repeated pattern at all
+pub fn smallest_block(num: &str) -> usize {
+    let digits = num.as_bytes();
+    block_sizes(digits.len())
+        .into_iter()
+        .find(|&n| digits.chunks(n).all(|chunk| chunk == &digits[..n]))
+        .unwrap_or(digits.len())
+}
+
 /// For a given range (where start and end are of the same length),
 /// generate all of the possible "invalid" numbers (those made of
-/// repeating patterns). This is method maps the length of numbers
-/// in the range to the kinds of patterns we can generate. See the
-/// long comment at the top of the file for an explanation. There
-/// is probably a more generalize way to do this, but I like seeing 
-/// it here in its expanded form.
-fn generate_all_numbers(start: String, end: String) -> Vec<String> {
-    let range_length = start.len();
-
-    // A hash is necessary so that we don't get repeats e.g. for 8-digit
-    // numbers, many of the 2-digit and 4 digit patterns will be the same
-    // E.g. In 11111111 - 22222222, will have 2 digit patterns like
-    // 11_11_11_11, 12_12_12_12, and 4 digit patterns like
-    // 1111_1111 and 1234_1234. We don't want to double count 11111111.
-    let mut output = HashSet::new();
-
-    match range_length {
-        1 => {
-            // digits of length 1 can't have a repeated pattern
-        }, 
-        2 => {
-            // println!("matching for length 2");
-            output.extend(generate_n_repeated_numbers(&start, &end, 1));
-        },
-        3 => { 
-            // println!("matching for length 3");
-            output.extend(generate_n_repeated_numbers(&start, &end, 1));
-        },
-        4 => { 
-            // println!("matching for length 4");
-            output.extend(generate_n_repeated_numbers(&start, &end, 1));
-            output.extend(generate_n_repeated_numbers(&start, &end, 2));
-        },
-        5 => { 
-            // println!("matching for length 5");
-            output.extend(generate_n_repeated_numbers(&start, &end, 1));
-        },
-        6 => { 
-            // println!("matching for length 6");
-            output.extend(generate_n_repeated_numbers(&start, &end, 1));
-            output.extend(generate_n_repeated_numbers(&start, &end, 2));
-            output.extend(generate_n_repeated_numbers(&start, &end, 3));
-
-        },
-        7 => { 
-            // println!("matching for length 7");
-            output.extend(generate_n_repeated_numbers(&start, &end, 1));
-        },
-        8 => { 
-            // println!("matching for length 8");
-            output.extend(generate_n_repeated_numbers(&start, &end, 1));
-            output.extend(generate_n_repeated_numbers(&start, &end, 2));
-            output.extend(generate_n_repeated_numbers(&start, &end, 4));
-
-        },
-        9 => { 
-            // println!("matching for length 9");
-            output.extend(generate_n_repeated_numbers(&start, &end, 1));
-            output.extend(generate_n_repeated_numbers(&start, &end, 3));
-        },
-        10 => { 
-            // println!("matching for length 10");
-            output.extend(generate_n_repeated_numbers(&start, &end, 1));
-            output.extend(generate_n_repeated_numbers(&start, &end, 2));
-            output.extend(generate_n_repeated_numbers(&start, &end, 5));
-
-        },
-        _ => println!("Length needs to be between 1 and 10")
-    }
-
-    return output.into_iter().collect()
+/// repeating patterns). Each block size only keeps the numbers that
+/// don't also repeat with a smaller block, so every number shows up once.
+pub fn generate_all_numbers(start: &str, end: &str) -> Vec<u128> {
+    block_sizes(start.len())
+        .into_iter()
+        .flat_map(|n| generate_n_repeated_numbers(start, end, n).into_iter().filter(move |num| smallest_block(&num.to_string()) == n))
+        .collect()
+}
+
+/// Add a number that's allowed to go past a u128 onto a running total
+/// that's allowed to as well. Both are kept as (how many times it wrapped
+/// around, what's left over), which is like having a 256 bit number.
+fn add_wide(total: &mut (u128, u128), num: (u128, u128)) {
+    let (low, wrapped) = total.1.overflowing_add(num.1);
+    *total = (total.0 + num.0 + wrapped as u128, low);
+}
+
+/// added - subtracted, as long as the answer fits back in a u128. added
+/// has to be at least as big as subtracted.
+fn wide_difference(added: (u128, u128), subtracted: (u128, u128)) -> Option<u128> {
+    let (low, borrowed) = added.1.overflowing_sub(subtracted.1);
+    match added.0 - subtracted.0 - borrowed as u128 {
+        0 => Some(low),
+        _ => None,
+    }
 }
 
+/// Multiply two u128s without losing anything off the top, by doing it in
+/// 64 bit halves like multiplying two digit numbers by hand. Hands back
+/// the same (wrapped, left over) pair as add_wide.
+fn mul_wide(a: u128, b: u128) -> (u128, u128) {
+    let half = u64::MAX as u128;
+    let (a_high, a_low) = (a >> 64, a & half);
+    let (b_high, b_low) = (b >> 64, b & half);
 
-/// Take the input content, break it up into its ranges (split and split_once)
+    let low_low = a_low * b_low;
+    let high_low = a_high * b_low;
+    let low_high = a_low * b_high;
+    let high_high = a_high * b_high;
+
+    let middle = (low_low >> 64) + (high_low & half) + (low_high & half);
+    let low = (middle << 64) | (low_low & half);
+    let high = high_high + (high_low >> 64) + (low_high >> 64) + (middle >> 64);
+
+    (high, low)
+}
+
+/// The sum of all of the "invalid" numbers in a range (where start and
+/// end are of the same length) using inclusion-exclusion over the block
+/// sizes, see the comment up top. None if it doesn't fit in a u128.
+fn sum_all_numbers(start: &str, end: &str) -> Option<u128> {
+    let length = start.len();
+
+    // The positive and negative terms are kept apart. Everything that gets
+    // subtracted was also added, so the difference can't go below zero,
+    // but the positive terms on their own can easily add up to more than a
+    // u128 for 39 digit IDs (333...3 gets added twice and taken away once)
+    // so those are kept wide until the end.
+    let mut added = (0, 0);
+    let mut subtracted = (0, 0);
+
+    for n in block_sizes(length) {
+        let total = match -mobius(length / n) {
+            1 => &mut added,
+            -1 => &mut subtracted,
+            _ => continue,
+        };
+        for num in generate_n_repeated_numbers(start, end, n) {
+            add_wide(total, (0, num));
+        }
+    }
+
+    wide_difference(added, subtracted)
+}
+
+// The generator still makes every single invalid number, which is fine for
+// the puzzle input but hopeless for a range like 1-10^30. A block p repeated
+// k times is p * 100..0100..01 (e.g. 123123123 = 123 * 1001001), so all of
+// the numbers made of n digit blocks are just the blocks times the same
+// multiplier. The blocks that land in the range are a run of consecutive
+// numbers, from the start divided by the multiplier (rounded up) to the end
+// divided by it (rounded down), and the sum of a run is the usual
+// (first + last) * how many / 2. No numbers need to be made at all.
+
+/// Which IDs are invalid. Part 1 only counts a block repeated exactly twice,
+/// part 2 counts a block repeated any number of times.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Repeats {
+    Twice,
+    AnyNumber,
+}
+
+/// How many invalid IDs there are and what they add up to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Totals {
+    pub count: u128,
+    pub sum: u128,
+}
+
+/// The first and last n digit blocks that repeat into a number between low
+/// and high (which are the same length), and what a block gets multiplied
+/// by to repeat it. None if there aren't any.
+fn block_bounds(low: &str, high: &str, n: usize) -> Option<(u128, u128, u128)> {
+    let length = low.len();
+    let low: u128 = low.parse().unwrap();
+    let high: u128 = high.parse().unwrap();
+
+    // e.g. 1001001 for 3 digit blocks in 9 digit numbers
+    let multiplier: u128 = (0..length / n).map(|i| u128::pow(10, (n * i) as u32)).sum();
+
+    // the blocks can't have a leading zero, and have to keep the repeated
+    // number inside the range
+    let first = u128::pow(10, (n - 1) as u32).max(low.div_ceil(multiplier));
+    let last = (u128::pow(10, n as u32) - 1).min(high / multiplier);
+
+    (first <= last).then_some((first, last, multiplier))
+}
+
+/// How many of the numbers between low and high (which are the same
+/// length) are an n digit block repeated, and their sum (which can be too
+/// big for a u128, so it's a (wrapped, left over) pair).
+fn repeated_series(low: &str, high: &str, n: usize) -> (u128, (u128, u128)) {
+    let Some((first, last, multiplier)) = block_bounds(low, high, n) else {
+        return (0, (0, 0));
+    };
+
+    // blocks are at most 19 digits, so this part can't overflow
+    let count = last - first + 1;
+    let block_sum = if count.is_multiple_of(2) { (first + last) * (count / 2) } else { (first + last) / 2 * count };
+
+    (count, mul_wide(block_sum, multiplier))
+}
+
+/// The count and sum of the invalid IDs between low and high (which are
+/// the same length) without generating any of them. None if the sum
+/// doesn't fit in a u128.
+fn series_totals_by_length(low: &str, high: &str, repeats: Repeats) -> Option<Totals> {
+    let length = low.len();
+
+    // (block size, whether it gets added or subtracted)
+    let terms: Vec<(usize, i32)> = match repeats {
+        Repeats::Twice if length.is_multiple_of(2) => vec![(length / 2, 1)],
+        Repeats::Twice => vec![],
+        Repeats::AnyNumber => block_sizes(length).into_iter().map(|n| (n, -mobius(length / n))).filter(|&(_, sign)| sign != 0).collect(),
+    };
+
+    let mut count_added = 0;
+    let mut count_subtracted = 0;
+    let mut added = (0, 0);
+    let mut subtracted = (0, 0);
+
+    for (n, sign) in terms {
+        let (count, sum) = repeated_series(low, high, n);
+        if sign > 0 {
+            count_added += count;
+            add_wide(&mut added, sum);
+        } else {
+            count_subtracted += count;
+            add_wide(&mut subtracted, sum);
+        }
+    }
+
+    Some(Totals { count: count_added - count_subtracted, sum: wide_difference(added, subtracted)? })
+}
+
+/// Count and add up the invalid IDs in every range of the input with
+/// arithmetic series instead of making each one. Errors if a range
+/// doesn't parse or the sum gets too big for a u128.
+pub fn series_totals(content: &str, repeats: Repeats) -> Result<Totals, ParseError> {
+    let mut totals = Totals { count: 0, sum: 0 };
+
+    for (start, end) in parse_ranges(content)? {
+        for (low, high) in number_ranges_by_length(start, end) {
+            let Some(added) = series_totals_by_length(&low, &high, repeats)
+                .and_then(|range| Some(Totals { count: totals.count + range.count, sum: totals.sum.checked_add(range.sum)? }))
+            else {
+                return Err(too_big(content, start, end));
+            };
+            totals = added;
+        }
+    }
+
+    Ok(totals)
+}
+
+// The report: for each range in the input, how many invalid IDs are in it,
+// which block sizes they're made of, and what they are. Everything in the
+// report is worked out from the block bounds like the series are, and the
+// IDs are only made up to the limit, so as long as there is a limit the
+// report for a range with trillions of invalid IDs is just as quick as one
+// with two. --report always has one (REPORT_LIMIT unless --limit says
+// otherwise). range_reports takes None to mean every ID, which is only
+// for ranges small enough to check one number at a time, like crosscheck's.
+//
+// An ID's block size is the smallest block that makes it, e.g. 11111111 is
+// a 1 digit block for part 2 even though 11 and 1111 repeat into it too.
+// For part 1 it's always half of the ID.
+
+/// How many IDs --report lists per range when --limit isn't given
+pub const REPORT_LIMIT: usize = 20;
+
+/// What a day 2 report says about one range of the input
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RangeReport {
+    pub start: String,
+    pub end: String,
+    pub count: u128,
+    /// (block size, how many IDs are made of blocks that size)
+    pub blocks: Vec<(usize, u128)>,
+    /// (ID, block size) for the first IDs in the range, up to the limit
+    pub ids: Vec<(u128, usize)>,
+}
+
+/// How many IDs between low and high (the same length) have n as their
+/// smallest block. Numbers made of n digit blocks include the ones made of
+/// smaller blocks that divide n, so those come off with the Mobius
+/// function the same way as the sums.
+fn count_smallest_block(low: &str, high: &str, n: usize) -> u128 {
+    let mut added = 0;
+    let mut subtracted = 0;
+
+    for d in (1..=n).filter(|d| n.is_multiple_of(*d)) {
+        match mobius(n / d) {
+            1 => added += repeated_series(low, high, d).0,
+            -1 => subtracted += repeated_series(low, high, d).0,
+            _ => {}
+        }
+    }
+
+    added - subtracted
+}
+
+/// Build the report for every range in the input, listing up to limit IDs
+/// per range. With no limit that's every ID, however many there are, so
+/// only leave it off for small ranges.
+pub fn range_reports(content: &str, repeats: Repeats, limit: Option<usize>) -> Result<Vec<RangeReport>, ParseError> {
+    let limit = limit.unwrap_or(usize::MAX);
+    let mut reports = vec![];
+
+    for (start, end) in parse_ranges(content)? {
+        let mut blocks: Vec<(usize, u128)> = vec![];
+        let mut ids = vec![];
+
+        for (low, high) in number_ranges_by_length(start, end) {
+            let length = low.len();
+            let sizes = match repeats {
+                Repeats::Twice if length.is_multiple_of(2) => vec![length / 2],
+                Repeats::Twice => vec![],
+                Repeats::AnyNumber => block_sizes(length),
+            };
+
+            let mut ids_by_length = vec![];
+            for n in sizes {
+                let count = match repeats {
+                    Repeats::Twice => repeated_series(&low, &high, n).0,
+                    Repeats::AnyNumber => count_smallest_block(&low, &high, n),
+                };
+                if count == 0 {
+                    continue;
+                }
+                match blocks.iter_mut().find(|(size, _)| *size == n) {
+                    Some((_, total)) => *total += count,
+                    None => blocks.push((n, count)),
+                }
+
+                // the IDs for each block size come out in order, so the
+                // first `limit` of each are enough to find the first
+                // `limit` overall
+                let Some((first, last, multiplier)) = block_bounds(&low, &high, n) else {
+                    continue;
+                };
+                let made_of_n = (first..=last)
+                    .map(|block| block * multiplier)
+                    .filter(|&id| repeats == Repeats::Twice || smallest_block(&id.to_string()) == n)
+                    .take(limit.saturating_sub(ids.len()))
+                    .map(|id| (id, n));
+                ids_by_length.extend(made_of_n);
+            }
+
+            // every ID of this length is smaller than the next length's
+            ids_by_length.sort();
+            ids.extend(ids_by_length.into_iter().take(limit.saturating_sub(ids.len())));
+        }
+
+        blocks.sort();
+        let count = blocks.iter().map(|(_, count)| count).sum();
+        reports.push(RangeReport { start: start.to_string(), end: end.to_string(), count, blocks, ids });
+    }
+
+    Ok(reports)
+}
+
+/// Print the reports as a table (one line per range), csv, or json
+pub fn format_reports(reports: &[RangeReport], format: OutputFormat) -> String {
+    let blocks = |report: &RangeReport, sep: &str| {
+        report.blocks.iter().map(|(size, count)| format!("{}:{}", size, count)).collect::<Vec<String>>().join(sep)
+    };
+    let ids = |report: &RangeReport, sep: &str| {
+        let mut ids: Vec<String> = report.ids.iter().map(|(id, _)| id.to_string()).collect();
+        if (ids.len() as u128) < report.count {
+            ids.push("...".to_string());
+        }
+        ids.join(sep)
+    };
+
+    match format {
+        OutputFormat::Table => {
+            let range_width = reports.iter().map(|r| r.start.len() + r.end.len() + 1).max().unwrap_or(0).max("range".len());
+            let blocks_width = reports.iter().map(|r| blocks(r, " ").len()).max().unwrap_or(0).max("size:count".len());
+            let mut lines = vec![format!("{:<range_width$} {:>6} {:<blocks_width$} ids", "range", "count", "size:count")];
+            for report in reports {
+                let range = format!("{}-{}", report.start, report.end);
+                lines.push(format!("{:<range_width$} {:>6} {:<blocks_width$} {}", range, report.count, blocks(report, " "), ids(report, " ")));
+            }
+            lines.join("\n")
+        }
+        OutputFormat::Csv => {
+            let mut lines = vec!["start,end,count,blocks,ids".to_string()];
+            for report in reports {
+                lines.push(format!("{},{},{},\"{}\",\"{}\"", report.start, report.end, report.count, blocks(report, " "), ids(report, " ")));
+            }
+            lines.join("\n")
+        }
+        // IDs are strings in the json since they can be too big for a lot
+        // of json parsers
+        OutputFormat::Json => {
+            let entries: Vec<String> = reports
+                .iter()
+                .map(|report| {
+                    let blocks: Vec<String> = report.blocks.iter().map(|(size, count)| format!("{{\"size\": {}, \"count\": {}}}", size, count)).collect();
+                    let ids: Vec<String> = report.ids.iter().map(|(id, size)| format!("{{\"id\": \"{}\", \"block\": {}}}", id, size)).collect();
+                    format!(
+                        "  {{\"start\": \"{}\", \"end\": \"{}\", \"count\": {}, \"blocks\": [{}], \"ids\": [{}], \"complete\": {}}}",
+                        report.start,
+                        report.end,
+                        report.count,
+                        blocks.join(", "),
+                        ids.join(", "),
+                        report.ids.len() as u128 == report.count,
+                    )
+                })
+                .collect();
+            format!("[\n{}\n]", entries.join(",\n"))
+        }
+    }
+}
+
+/// range_reports and format_reports in one go for the solvers
+pub fn report(content: &str, repeats: Repeats, options: ReportOptions) -> Result<String, ParseError> {
+    let limit = options.limit.unwrap_or(REPORT_LIMIT);
+    Ok(format_reports(&range_reports(content, repeats, Some(limit))?, options.format))
+}
+
+/// The error for when the invalid IDs add up to more than a u128, pointing
+/// at the range that tipped it over
+fn too_big(content: &str, start: &str, end: &str) -> ParseError {
+    let offset = offset_in(content, start);
+    let range = &content[offset..offset + start.len() + 1 + end.len()];
+    ParseError::at_offset(content, offset, range, "the invalid IDs add up to more than a u128 can hold")
+}
+
+
+/// Take the input content, break it up into its ranges (parse_ranges from part 1)
 /// for each range provided by the input text, break that up into ranges by size (number_ranges_by_length)
-/// for each of the ranges of same size, generate all possible number patterns (generate_all_numbers)
-/// Then sum up all of the generated numbers.
-pub fn solve(content: &str) {
-
-    // Does most of the work to transform the ranges into number patterns ("invalid numbers")
-    let invalid_numbers: Vec<String> = content.split(',')
-        .map(|content_range| content_range.split_once('-').expect("Format should be start-end"))
-        .flat_map(|pair| number_ranges_by_length(pair.0, pair.1))
-        .flat_map(|sub_range| generate_all_numbers(sub_range.0, sub_range.1))
-        .collect();
-
-    // Take the numbers we generated as strings, converts them to integers, and sums them
-    println!("final sum: {}", invalid_numbers.into_iter().map(|i| i.parse::<u64>().unwrap()).sum::<u64>())
+/// for each of the ranges of same size, add up all of the possible number patterns (sum_all_numbers)
+pub fn solve(content: &str) -> Result<Answer, ParseError> {
+    let mut total: u128 = 0;
+
+    for (start, end) in parse_ranges(content)? {
+        for (low, high) in number_ranges_by_length(start, end) {
+            let Some(sum) = sum_all_numbers(&low, &high).and_then(|sum| total.checked_add(sum)) else {
+                return Err(too_big(content, start, end));
+            };
+            total = sum;
+        }
+    }
+
+    Ok(total.into())
 }
 
+
+pub struct Solution;
+
+impl Solver for Solution {
+    fn day(&self) -> u8 {
+        2
+    }
+
+    fn part(&self) -> u8 {
+        2
+    }
+
+    fn name(&self) -> &'static str {
+        "sum of IDs made of any repeated pattern"
+    }
+
+    fn parse(&self, content: &str) -> Option<Result<(), ParseError>> {
+        Some(parse_ranges(content).map(|_| ()))
+    }
+
+    fn solve(&self, content: &str) -> Result<Answer, ParseError> {
+        solve(content)
+    }
+
+    fn report(&self, content: &str, options: ReportOptions) -> Option<Result<String, ParseError>> {
+        Some(report(content, Repeats::AnyNumber, options))
+    }
+}
+
+/// Part 2 with arithmetic series instead of generating the IDs
+pub struct SeriesSolution;
+
+impl Solver for SeriesSolution {
+    fn day(&self) -> u8 {
+        2
+    }
+
+    fn part(&self) -> u8 {
+        2
+    }
+
+    fn name(&self) -> &'static str {
+        "sum of IDs made of any repeated pattern with series"
+    }
+
+    fn variant(&self) -> Option<&'static str> {
+        Some("series")
+    }
+
+    fn parse(&self, content: &str) -> Option<Result<(), ParseError>> {
+        Some(parse_ranges(content).map(|_| ()))
+    }
+
+    fn solve(&self, content: &str) -> Result<Answer, ParseError> {
+        Ok(series_totals(content, Repeats::AnyNumber)?.sum.into())
+    }
+
+    fn report(&self, content: &str, options: ReportOptions) -> Option<Result<String, ParseError>> {
+        Some(report(content, Repeats::AnyNumber, options))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::cases;
+
+    fn sorted(mut numbers: Vec<u128>) -> Vec<u128> {
+        numbers.sort();
+        numbers
+    }
+
+    #[test]
+    fn number_ranges_by_length_splits_on_powers_of_ten() {
+        assert_eq!(
+            number_ranges_by_length("4", "234"),
+            vec![("4".to_string(), "9".to_string()), ("10".to_string(), "99".to_string()), ("100".to_string(), "234".to_string())]
+        );
+        assert_eq!(number_ranges_by_length("11", "22"), vec![("11".to_string(), "22".to_string())]);
+    }
+
+    #[test]
+    fn number_ranges_by_length_up_to_39_digits() {
+        let max = u128::MAX.to_string();
+        let ranges = number_ranges_by_length("5", &max);
+        assert_eq!(ranges.len(), 39);
+        assert_eq!(ranges[37], (format!("1{}", "0".repeat(37)), "9".repeat(38)));
+        assert_eq!(ranges[38], (format!("1{}", "0".repeat(38)), max));
+    }
+
+    #[test]
+    fn generate_n_repeated_numbers_single_digit() {
+        assert_eq!(sorted(generate_n_repeated_numbers("200", "400", 1)), vec![222, 333]);
+    }
+
+    #[test]
+    fn generate_n_repeated_numbers_bounds_from_start() {
+        // 121212 is below the start, so the first one is 131313
+        assert_eq!(sorted(generate_n_repeated_numbers("123456", "141414", 2)), vec![131313, 141414]);
+    }
+
+    #[test]
+    fn generate_n_repeated_numbers_bounds_from_end() {
+        // 464646 is past the end, so the last one is 454545
+        assert_eq!(sorted(generate_n_repeated_numbers("440000", "456789", 2)), vec![444444, 454545]);
+
+        // with 654321, 65 can't be repeated without going over so 64 is the highest
+        assert_eq!(sorted(generate_n_repeated_numbers("630000", "654321", 2)), vec![636363, 646464]);
+    }
+
+    #[test]
+    fn generate_n_repeated_numbers_bigger_blocks() {
+        assert_eq!(sorted(generate_n_repeated_numbers("123000", "125999", 3)), vec![123123, 124124, 125125]);
+    }
+
+    #[test]
+    fn generate_n_repeated_numbers_none_in_range() {
+        assert!(generate_n_repeated_numbers("1234", "1299", 2).is_empty());
+    }
+
+    #[test]
+    fn block_sizes_and_mobius() {
+        assert_eq!(block_sizes(1), Vec::<usize>::new());
+        assert_eq!(block_sizes(7), vec![1]);
+        assert_eq!(block_sizes(12), vec![1, 2, 3, 4, 6]);
+        assert_eq!(block_sizes(39), vec![1, 3, 13]);
+
+        let mobius_values: Vec<i32> = (1..=12).map(mobius).collect();
+        assert_eq!(mobius_values, vec![1, -1, -1, 0, -1, 1, -1, 0, 0, 1, -1, 0]);
+    }
+
+    #[test]
+    fn smallest_block_finds_the_shortest_repeat() {
+        assert_eq!(smallest_block("11111111"), 1);
+        assert_eq!(smallest_block("12121212"), 2);
+        assert_eq!(smallest_block("123123"), 3);
+        assert_eq!(smallest_block("123124"), 6);
+        assert_eq!(smallest_block("7"), 1);
+    }
+
+    #[test]
+    fn generate_all_numbers_does_not_double_count() {
+        // 11111111 is both a 1 digit, 2 digit, and 4 digit pattern
+        assert_eq!(sorted(generate_all_numbers("11111111", "11111111")), vec![11111111]);
+        assert_eq!(sorted(generate_all_numbers("1000", "1111")), vec![1010, 1111]);
+    }
+
+    // Check the generator and the inclusion-exclusion sum against checking
+    // every number in some small ranges, including lengths past the 10
+    // digits the old match went up to
+    #[test]
+    fn matches_checking_every_number() {
+        let ranges = [
+            ("1", "9999"),
+            ("100000", "130000"),
+            ("1212120", "1313131"),
+            ("121212121200", "121212141414"),
+            ("999999999999990", "999999999999999"),
+        ];
+
+        for (start, end) in ranges {
+            let low: u128 = start.parse().unwrap();
+            let high: u128 = end.parse().unwrap();
+            let expected: Vec<u128> = (low..=high).filter(|n| {
+                let n = n.to_string();
+                smallest_block(&n) < n.len()
+            }).collect();
+
+            let generated: Vec<u128> = number_ranges_by_length(start, end)
+                .iter()
+                .flat_map(|(low, high)| generate_all_numbers(low, high))
+                .collect();
+            assert_eq!(sorted(generated), expected, "{}-{}", start, end);
+
+            let summed: u128 = number_ranges_by_length(start, end).iter().map(|(low, high)| sum_all_numbers(low, high).unwrap()).sum();
+            assert_eq!(summed, expected.iter().sum::<u128>(), "{}-{}", start, end);
+        }
+    }
+
+    #[test]
+    fn ids_up_to_a_u128() {
+        let ones = "1".repeat(39);
+        assert_eq!(solve(&format!("{}-{}", ones, ones)).unwrap(), Answer::from(ones.parse::<u128>().unwrap()));
+
+        let twelves = "12".repeat(19);
+        assert_eq!(solve(&format!("{}-{}", twelves, twelves)).unwrap(), Answer::from(twelves.parse::<u128>().unwrap()));
+
+        // 333...3 fits, but two of them don't
+        let threes = "3".repeat(39);
+        let err = solve(&format!("{}-{},{}-{}", threes, threes, threes, threes)).unwrap_err();
+        assert_eq!(err.column, 81);
+        assert_eq!(err.message, "the invalid IDs add up to more than a u128 can hold");
+    }
+
+    #[test]
+    fn mul_wide_keeps_the_top() {
+        assert_eq!(mul_wide(3, 7), (0, 21));
+        assert_eq!(mul_wide(1 << 64, 1 << 64), (1, 0));
+        assert_eq!(mul_wide(u128::MAX, u128::MAX), (u128::MAX - 1, 1));
+        assert_eq!(mul_wide(u128::MAX, 2), (1, u128::MAX - 1));
+    }
+
+    #[test]
+    fn repeated_series_counts_and_sums() {
+        // 222 and 333
+        assert_eq!(repeated_series("200", "400", 1), (2, (0, 555)));
+        // 131313 and 141414
+        assert_eq!(repeated_series("123456", "141414", 2), (2, (0, 272727)));
+        assert_eq!(repeated_series("1234", "1299", 2), (0, (0, 0)));
+    }
+
+    // Lots of random ranges, the series have to agree with the generator
+    // for part 2 and with checking both halves for part 1
+    #[test]
+    fn series_match_the_generator_on_random_ranges() {
+        cases(0x853c49e6748fea9b, 300, |rng| {
+            let length = rng.below(10) as u32 + 1;
+            let start = rng.next_u64() as u128 % u128::pow(10, length) + 1;
+            let end = start + rng.next_u64() as u128 % 20000;
+            let content = format!("{}-{}", start, end);
+
+            let any = series_totals(&content, Repeats::AnyNumber).unwrap();
+            let generated: Vec<u128> = number_ranges_by_length(&start.to_string(), &end.to_string())
+                .iter()
+                .flat_map(|(low, high)| generate_all_numbers(low, high))
+                .collect();
+            assert_eq!(any.count, generated.len() as u128, "{}", content);
+            assert_eq!(any.sum, generated.iter().sum::<u128>(), "{}", content);
+            assert_eq!(Answer::from(any.sum), solve(&content).unwrap(), "{}", content);
+
+            let twice: Vec<u128> = (start..=end).filter(|n| {
+                let n = n.to_string();
+                n.len().is_multiple_of(2) && n[..n.len() / 2] == n[n.len() / 2..]
+            }).collect();
+            let series = series_totals(&content, Repeats::Twice).unwrap();
+            assert_eq!(series.count, twice.len() as u128, "{}", content);
+            assert_eq!(series.sum, twice.iter().sum::<u128>(), "{}", content);
+        });
+    }
+
+    #[test]
+    fn series_handle_huge_ranges() {
+        // 999...9 twice is the biggest number made of a block repeated twice
+        // in 24 digits, and all of them are every 12 digit block times
+        // 1000000000001
+        let content = format!("1-{}", "9".repeat(24));
+        let totals = series_totals(&content, Repeats::Twice).unwrap();
+        assert_eq!(totals.count, (1..=12).map(|n| 9 * u128::pow(10, n - 1)).sum::<u128>());
+
+        // the 39 digit examples from the generator work too
+        let threes = "3".repeat(39);
+        assert_eq!(series_totals(&format!("{}-{}", threes, threes), Repeats::AnyNumber).unwrap(), Totals { count: 1, sum: threes.parse().unwrap() });
+
+        // and the whole of u128 is way too much to add up, but the error
+        // comes back straight away instead of after generating everything
+        let err = series_totals(&format!("1-{}", u128::MAX), Repeats::AnyNumber).unwrap_err();
+        assert_eq!(err.message, "the invalid IDs add up to more than a u128 can hold");
+    }
+
+    #[test]
+    fn reports_list_the_ids_and_block_sizes() {
+        let reports = range_reports("95-115,998-1012,11111110-11111112", Repeats::AnyNumber, None).unwrap();
+        assert_eq!(reports[0], RangeReport { start: "95".to_string(), end: "115".to_string(), count: 2, blocks: vec![(1, 2)], ids: vec![(99, 1), (111, 1)] });
+        assert_eq!(reports[1].blocks, vec![(1, 1), (2, 1)]);
+        assert_eq!(reports[1].ids, vec![(999, 1), (1010, 2)]);
+        // 11111111 is a 1 digit block, not a 2 or 4 digit one
+        assert_eq!(reports[2].ids, vec![(11111111, 1)]);
+
+        let twice = range_reports("95-115,998-1012", Repeats::Twice, None).unwrap();
+        assert_eq!(twice[0].ids, vec![(99, 1)]);
+        assert_eq!(twice[1].ids, vec![(1010, 2)]);
+    }
+
+    #[test]
+    fn reports_stop_at_the_limit() {
+        // the first 3 IDs are the smallest ones, from whichever block size
+        let reports = range_reports("1-10000", Repeats::AnyNumber, Some(3)).unwrap();
+        assert_eq!(reports[0].ids, vec![(11, 1), (22, 1), (33, 1)]);
+        assert_eq!(reports[0].count, 9 + 9 + 90);
+
+        let reports = range_reports("100-2000", Repeats::AnyNumber, Some(3)).unwrap();
+        assert_eq!(reports[0].ids, vec![(111, 1), (222, 1), (333, 1)]);
+
+        let reports = range_reports("1000-1111", Repeats::AnyNumber, Some(0)).unwrap();
+        assert_eq!(reports[0].ids, vec![]);
+        assert_eq!(reports[0].count, 2);
+    }
+
+    #[test]
+    fn report_has_a_limit_without_one_given() {
+        // 10^19 repeated IDs, but only REPORT_LIMIT of them get listed
+        let content = format!("1-{}", "9".repeat(38));
+        let csv = report(&content, Repeats::Twice, ReportOptions::new(OutputFormat::Csv)).unwrap();
+        let ids = csv.lines().nth(1).unwrap().rsplit(',').next().unwrap().trim_matches('"');
+        // and a ... on the end to show there are more
+        assert_eq!(ids.split(' ').filter(|id| *id != "...").count(), REPORT_LIMIT);
+        assert!(ids.ends_with("..."));
+
+        let options = ReportOptions { format: OutputFormat::Csv, limit: Some(2) };
+        assert!(report("11-99", Repeats::Twice, options).unwrap().ends_with("\"11 22 ...\""));
+    }
+
+    #[test]
+    fn report_counts_match_the_series() {
+        for content in ["1-99999999", "123456-99999999999", &format!("1-{}", "9".repeat(24))] {
+            for repeats in [Repeats::Twice, Repeats::AnyNumber] {
+                let reports = range_reports(content, repeats, Some(10)).unwrap();
+                let by_block: u128 = reports[0].blocks.iter().map(|(_, count)| count).sum();
+                assert_eq!(reports[0].count, series_totals(content, repeats).unwrap().count, "{}", content);
+                assert_eq!(by_block, reports[0].count);
+                assert_eq!(reports[0].ids.len(), 10);
+            }
+        }
+    }
+
+    #[test]
+    fn report_formats() {
+        let reports = range_reports("95-115,1-9", Repeats::AnyNumber, Some(1)).unwrap();
+        assert_eq!(
+            format_reports(&reports, OutputFormat::Table),
+            "range   count size:count ids\n95-115      2 1:2        99 ...\n1-9         0            "
+        );
+        assert_eq!(format_reports(&reports, OutputFormat::Csv), "start,end,count,blocks,ids\n95,115,2,\"1:2\",\"99 ...\"\n1,9,0,\"\",\"\"");
+        assert_eq!(
+            format_reports(&reports[..1], OutputFormat::Json),
+            "[\n  {\"start\": \"95\", \"end\": \"115\", \"count\": 2, \"blocks\": [{\"size\": 1, \"count\": 2}], \"ids\": [{\"id\": \"99\", \"block\": 1}], \"complete\": false}\n]"
+        );
+    }
+}
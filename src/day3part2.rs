@@ -1,3 +1,8 @@
+use crate::answer::Answer;
+use crate::day3part1::check_banks;
+use crate::error::ParseError;
+use crate::rmq::{RangeMax, SegmentTree, SparseTable};
+use crate::solver::{OutputFormat, ReportOptions, Solver};
 
 // Given a string of digits, return the 12 highest digits in the order they are found e.g. 
 // 987654321111111 = 987654321111
@@ -51,20 +56,67 @@
 // Oh and I guess a sparse table would work as well and have better retrieval. n log n
 // to build and constant time to retreive, but takes n log n space as well.
 
+// Update: both of those exist now in rmq.rs, and max_subsequence_number_rmq
+// below does the same range scanning with either of them answering the
+// "where's the biggest digit in this range" question.
+//
+// There's also a completely different O(i) way to do it with a stack. We
+// are allowed to throw away i - o digits. Walk the input and push each
+// digit on the stack, but before pushing, pop anything smaller than it off
+// the top (as long as we still have throw aways left) since a bigger digit
+// earlier always beats a smaller one. Whatever is at the bottom of the
+// stack at the end is the answer. Each digit gets pushed and popped at
+// most once, so it's linear no matter what o is.
+
 
 // Let's generalize the solution. I'd like to see if this works for part 1 as well.
-const SOLUTION_LENGTH: usize = 2;
+// It does! The length used to be a const that was left at 2 from checking
+// against part 1, which meant part 2 was really just solving part 1 again.
+// Now it's a setting on the solver (and `--length N` on the command line)
+// and part 2 uses the 12 the puzzle asks for by default. Part 1 calls
+// max_subsequence_number with a length of 2 now too, instead of keeping
+// its own two digit scan around.
+pub const DEFAULT_LENGTH: usize = 12;
+
+/// Which batteries got turned on in a bank (their indexes, in order) and
+/// the joltage they make. The joltage stays a string of digits since it
+/// can be way longer than any integer type holds.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Selection {
+    pub indexes: Vec<usize>,
+    pub value: String,
+}
+
+impl Selection {
+    fn from_indexes(digits: &[u8], indexes: Vec<usize>) -> Self {
+        let value = indexes.iter().map(|&idx| (b'0' + digits[idx]) as char).collect();
+        Selection { indexes, value }
+    }
+}
+
+/// Turn a bank into its digits (as 0-9, not ascii). Run check_bank on it
+/// first, anything that isn't a digit turns into garbage.
+pub fn bank_digits(line: &str) -> Vec<u8> {
+    line.bytes().map(|b| b - b'0').collect()
+}
 
+/// Pick k of the digits, keeping them in order, to make the biggest
+/// number possible. This is what both parts of day 3 are asking for, part
+/// 1 with k = 2 and part 2 with k = 12. Panics if there are fewer than k
+/// digits.
+///
 /// Scan the input o times where o is the length of the output.
 /// There are definitely some optimizations I could work into here, but
 /// for now I just want to see what a straightforward algo looks like
-fn find_highest_numbers(line: &str) -> String {
+pub fn max_subsequence_number(digits: &[u8], k: usize) -> Selection {
+    assert!(k <= digits.len(), "can't pick {} digits out of {}", k, digits.len());
 
-    let input_vec = line.chars().map(|c| c.to_digit(10).unwrap() as i8).collect::<Vec<i8>>();
-    let mut output_vec: Vec<i8> = vec![];
+    // These used to be i8s, which is fine for single digits but the
+    // indexes overflowed on any bank longer than 127 batteries
+    let mut output_vec: Vec<usize> = vec![];
 
     // For each slot in the output we need to build
-    for output_index in 0..SOLUTION_LENGTH {
+    for output_index in 0..k {
         
         // determine the segment of the array we are searching
 
@@ -73,7 +125,7 @@ fn find_highest_numbers(line: &str) -> String {
         // recently saved index plus 1. This is some Rust
         // nonsense that basically says output_vec.last()
         // has a value, return it plus one, otherwise return 0
-        let start: i8 = match output_vec.last() {
+        let start: usize = match output_vec.last() {
             Some(&x) => x + 1,
             None => 0,
         };
@@ -84,16 +136,18 @@ fn find_highest_numbers(line: &str) -> String {
         // fill the rest of our output. That's going to be
         // the length of the input - the length of the solution
         // plus which digit of the solution we are trying to fill
-        let end: i8 = (input_vec.len() - SOLUTION_LENGTH + output_index).try_into().unwrap();
+        let end: usize = digits.len() - k + output_index;
 
         // Now, find the index of the highest digit in this range. 
         // Since 9 is the highest possible digit, one could optimize
         // here to return early on a 9.
-        // highest_number_tuple is a tuple of (index, value) 
-        let mut highest_number_tuple = (-1, -1);
-        for input_index in start..=end {
-            if input_vec[input_index as usize] > highest_number_tuple.1 {
-                highest_number_tuple = (input_index, input_vec[input_index as usize]);
+        // highest_number_tuple is a tuple of (index, value). Starting
+        // on the first digit in the range means we don't need a -1
+        // for "nothing found yet" anymore.
+        let mut highest_number_tuple = (start, digits[start]);
+        for (input_index, &digit) in digits.iter().enumerate().take(end + 1).skip(start) {
+            if digit > highest_number_tuple.1 {
+                highest_number_tuple = (input_index, digit);
             }
         }
 
@@ -103,21 +157,351 @@ fn find_highest_numbers(line: &str) -> String {
     }
 
     // At this point, we should have completely filled the output vec with the 
-    // indexes of the numbers we want to save, so we need to extract those. Then 
-    // because the next step is to turn these digits into a large number, we need
-    // to convert it from a vec of ints back into a string so that string can be
-    // turned into a huge number. This is some Rust nonsense that does that all
-    // at once and implicitly returns.
-    output_vec.into_iter().map(|idx| input_vec[idx as usize].to_string()).collect()
+    // indexes of the numbers we want to save. Those get handed back along
+    // with the digits they point at, so whoever called this can show which
+    // batteries were picked and not just the joltage.
+    Selection::from_indexes(digits, output_vec)
+}
+
+/// The same range scanning as max_subsequence_number, but asking a range
+/// max structure (a segment tree or a sparse table) for the biggest digit
+/// in each range instead of scanning it.
+fn max_subsequence_number_rmq<Q: RangeMax<u8>>(digits: &[u8], k: usize) -> Selection {
+    assert!(k <= digits.len(), "can't pick {} digits out of {}", k, digits.len());
+    let rmq = Q::build(digits);
+
+    let mut indexes = Vec::with_capacity(k);
+    let mut start = 0;
+    for output_index in 0..k {
+        let end = digits.len() - k + output_index;
+        let idx = rmq.leftmost_max(start, end);
+        indexes.push(idx);
+        start = idx + 1;
+    }
+
+    Selection::from_indexes(digits, indexes)
+}
+
+/// The stack approach from the comment up top. The stack holds indexes so
+/// we know which batteries are left on it at the end.
+fn max_subsequence_number_stack(digits: &[u8], k: usize) -> Selection {
+    assert!(k <= digits.len(), "can't pick {} digits out of {}", k, digits.len());
+    let mut throw_aways = digits.len() - k;
+    let mut stack: Vec<usize> = Vec::with_capacity(digits.len());
+
+    for (idx, &digit) in digits.iter().enumerate() {
+        while throw_aways > 0 && stack.last().is_some_and(|&top| digits[top] < digit) {
+            stack.pop();
+            throw_aways -= 1;
+        }
+        stack.push(idx);
+    }
+
+    // if we never ran out of throw aways, the extras are on the end
+    stack.truncate(k);
+    Selection::from_indexes(digits, stack)
+}
+
+/// Add a number (as a string of digits) onto a running total kept as a
+/// list of digits, least significant first. The joltages can be as long
+/// as the banks are, which is way more digits than any integer type holds,
+/// so this is grade school addition with carrying.
+fn add_decimal(total: &mut Vec<u8>, number: &str) {
+    let mut carry = 0;
+
+    for (place, digit) in number.bytes().rev().map(|b| b - b'0').enumerate() {
+        if place == total.len() {
+            total.push(0);
+        }
+        let sum = total[place] + digit + carry;
+        total[place] = sum % 10;
+        carry = sum / 10;
+    }
+
+    let mut place = number.len();
+    while carry > 0 {
+        if place == total.len() {
+            total.push(0);
+        }
+        let sum = total[place] + carry;
+        total[place] = sum % 10;
+        carry = sum / 10;
+        place += 1;
+    }
+}
+
+/// The different ways of picking the digits. They should all give the
+/// same answer, they just get there differently.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Method {
+    Scan,
+    SegmentTree,
+    SparseTable,
+    Stack,
+}
+
+impl Method {
+    pub const ALL: [Method; 4] = [Method::Scan, Method::SegmentTree, Method::SparseTable, Method::Stack];
+
+    /// Pick k of the digits the way this method does it. Every method
+    /// gives back exactly the same selection as max_subsequence_number.
+    pub fn select(self, digits: &[u8], k: usize) -> Selection {
+        match self {
+            Method::Scan => max_subsequence_number(digits, k),
+            Method::SegmentTree => max_subsequence_number_rmq::<SegmentTree<u8>>(digits, k),
+            Method::SparseTable => max_subsequence_number_rmq::<SparseTable<u8>>(digits, k),
+            Method::Stack => max_subsequence_number_stack(digits, k),
+        }
+    }
+}
+
+/// Solve using the given way of picking digits, turning on `length`
+/// batteries in each bank
+pub fn solve_with(content: &str, method: Method, length: usize) -> Result<Answer, ParseError> {
+
+    check_banks(content, length)?;
+
+    let mut total = vec![];
+    for line in content.lines() {
+        add_decimal(&mut total, &method.select(&bank_digits(line), length).value);
+    }
+
+    // most of the time this fits in an Int, but a long enough length
+    // means it has to be shown as text
+    let solution: String = total.iter().rev().map(|d| (b'0' + d) as char).collect();
+    match solution.parse::<i128>() {
+        Ok(solution) => Ok(solution.into()),
+        Err(_) if solution.is_empty() => Ok(0.into()),
+        Err(_) => Ok(solution.into()),
+    }
+}
+
+pub fn solve(content: &str) -> Result<Answer, ParseError> {
+    solve_with(content, Method::Scan, DEFAULT_LENGTH)
+}
+
+/// Show which batteries got turned on in each bank and the joltage that
+/// made. Battery positions are counted from 1 like the columns in a
+/// ParseError. Joltages are strings in the json since they can be too
+/// long for a number.
+pub fn report(content: &str, method: Method, length: usize, format: OutputFormat) -> Result<String, ParseError> {
+    check_banks(content, length)?;
+
+    let selections: Vec<Selection> = content.lines().map(|line| method.select(&bank_digits(line), length)).collect();
+    let positions = |selection: &Selection| selection.indexes.iter().map(|idx| idx + 1).collect::<Vec<usize>>();
+    let joined = |positions: Vec<usize>, sep: &str| positions.iter().map(|p| p.to_string()).collect::<Vec<String>>().join(sep);
+
+    let lines: Vec<String> = match format {
+        OutputFormat::Table => {
+            let width = selections.iter().map(|s| s.value.len()).max().unwrap_or(0).max("joltage".len());
+            let header = format!("{:>6} {:<width$} batteries", "bank", "joltage", width = width);
+            let rows = selections.iter().enumerate().map(|(bank, selection)| {
+                format!("{:>6} {:<width$} {}", bank + 1, selection.value, joined(positions(selection), " "), width = width)
+            });
+            std::iter::once(header).chain(rows).collect()
+        }
+        OutputFormat::Csv => {
+            let rows = selections.iter().enumerate().map(|(bank, selection)| {
+                format!("{},{},\"{}\"", bank + 1, selection.value, joined(positions(selection), " "))
+            });
+            std::iter::once("bank,joltage,batteries".to_string()).chain(rows).collect()
+        }
+        OutputFormat::Json => {
+            let entries: Vec<String> = selections
+                .iter()
+                .enumerate()
+                .map(|(bank, selection)| {
+                    format!("  {{\"bank\": {}, \"joltage\": \"{}\", \"batteries\": [{}]}}", bank + 1, selection.value, joined(positions(selection), ", "))
+                })
+                .collect();
+            vec![format!("[\n{}\n]", entries.join(",\n"))]
+        }
+    };
+
+    Ok(lines.join("\n"))
+}
+
+pub struct Solution;
+
+impl Solver for Solution {
+    fn day(&self) -> u8 {
+        3
+    }
+
+    fn part(&self) -> u8 {
+        2
+    }
+
+    fn name(&self) -> &'static str {
+        "highest joltage per bank by range scanning"
+    }
+
+    fn parse(&self, content: &str) -> Option<Result<(), ParseError>> {
+        Some(check_banks(content, DEFAULT_LENGTH).map(|_| ()))
+    }
+
+    fn solve(&self, content: &str) -> Result<Answer, ParseError> {
+        solve(content)
+    }
+
+    fn report(&self, content: &str, options: ReportOptions) -> Option<Result<String, ParseError>> {
+        Some(report(content, Method::Scan, DEFAULT_LENGTH, options.format))
+    }
+
+    fn with_length(&self, length: usize) -> Option<Box<dyn Solver>> {
+        Some(Box::new(MethodSolution { method: Method::Scan, length }))
+    }
 }
 
-pub fn solve(content: &str) {
+/// Part 2 using one of the other methods, registered as a variant e.g.
+/// 3-2-stack, and/or with a different number of batteries per bank.
+/// MethodSolution::new(Method::Scan) is the same thing as Solution.
+pub struct MethodSolution {
+    pub method: Method,
+    pub length: usize,
+}
 
-    let joltages: Vec<String> = content.lines()
-        .map(|line| find_highest_numbers(line))
-        .collect();
+impl MethodSolution {
+    pub fn new(method: Method) -> Self {
+        MethodSolution { method, length: DEFAULT_LENGTH }
+    }
+}
+
+impl Solver for MethodSolution {
+    fn day(&self) -> u8 {
+        3
+    }
+
+    fn part(&self) -> u8 {
+        2
+    }
+
+    fn name(&self) -> &'static str {
+        match self.method {
+            Method::Scan => "highest joltage per bank by range scanning",
+            Method::SegmentTree => "highest joltage per bank with a segment tree",
+            Method::SparseTable => "highest joltage per bank with a sparse table",
+            Method::Stack => "highest joltage per bank with a monotonic stack",
+        }
+    }
+
+    fn variant(&self) -> Option<&'static str> {
+        match self.method {
+            Method::Scan => None,
+            Method::SegmentTree => Some("segtree"),
+            Method::SparseTable => Some("sparse"),
+            Method::Stack => Some("stack"),
+        }
+    }
+
+    fn parse(&self, content: &str) -> Option<Result<(), ParseError>> {
+        Some(check_banks(content, self.length).map(|_| ()))
+    }
+
+    fn solve(&self, content: &str) -> Result<Answer, ParseError> {
+        solve_with(content, self.method, self.length)
+    }
+
+    fn report(&self, content: &str, options: ReportOptions) -> Option<Result<String, ParseError>> {
+        Some(report(content, self.method, self.length, options.format))
+    }
 
-    let solution: i64 = joltages.into_iter().map(|joltage| joltage.parse::<i64>().unwrap()).sum();
-    
-    println!("solution: {}", solution);
-}
\ No newline at end of file
+    fn with_length(&self, length: usize) -> Option<Box<dyn Solver>> {
+        Some(Box::new(MethodSolution { method: self.method, length }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::cases;
+
+    #[test]
+    fn methods_agree_on_the_examples() {
+        let examples = [
+            ("987654321111111", 2, "98", vec![0, 1]),
+            ("811111111111119", 2, "89", vec![0, 14]),
+            ("234234234234278", 2, "78", vec![13, 14]),
+            ("818181911112111", 2, "92", vec![6, 11]),
+            ("987654321111111", 12, "987654321111", (0..12).collect()),
+            ("811111111111119", 12, "811111111119", (0..11).chain([14]).collect()),
+            ("234234234234278", 12, "434234234278", vec![2, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14]),
+            ("818181911112111", 12, "888911112111", vec![0, 2, 4, 6, 7, 8, 9, 10, 11, 12, 13, 14]),
+        ];
+
+        for (line, length, value, indexes) in examples {
+            let expected = Selection { indexes, value: value.to_string() };
+            for method in Method::ALL {
+                assert_eq!(method.select(&bank_digits(line), length), expected, "{:?} on {}", method, line);
+            }
+        }
+    }
+
+    #[test]
+    fn long_banks_and_lengths() {
+        // 200 batteries is past where the old i8 indexes overflowed, and
+        // a 40 digit joltage is past what even a u128 can hold
+        let line: String = (0..200).map(|i| char::from(b'0' + (i * 7 % 10) as u8)).collect();
+        let digits = bank_digits(&line);
+        for method in Method::ALL {
+            assert_eq!(method.select(&digits, 40), max_subsequence_number(&digits, 40));
+        }
+
+        let content = format!("{}\n{}", "9".repeat(40), "9".repeat(40));
+        assert_eq!(solve_with(&content, Method::Stack, 40).unwrap().to_string(), format!("1{}8", "9".repeat(39)));
+    }
+
+    #[test]
+    fn picking_everything_or_nothing() {
+        let digits = bank_digits("3141");
+        for method in Method::ALL {
+            assert_eq!(method.select(&digits, 4), Selection { indexes: vec![0, 1, 2, 3], value: "3141".to_string() });
+            assert_eq!(method.select(&digits, 0), Selection { indexes: vec![], value: String::new() });
+        }
+    }
+
+    #[test]
+    fn report_shows_the_batteries() {
+        let content = "987654321111111\n811111111111119";
+        let table = report(content, Method::Scan, 2, OutputFormat::Table).unwrap();
+        assert_eq!(table, "  bank joltage batteries\n     1 98      1 2\n     2 89      1 15");
+
+        let csv = report(content, Method::Stack, 2, OutputFormat::Csv).unwrap();
+        assert_eq!(csv, "bank,joltage,batteries\n1,98,\"1 2\"\n2,89,\"1 15\"");
+
+        let json = report(content, Method::SparseTable, 2, OutputFormat::Json).unwrap();
+        assert_eq!(json, "[\n  {\"bank\": 1, \"joltage\": \"98\", \"batteries\": [1, 2]},\n  {\"bank\": 2, \"joltage\": \"89\", \"batteries\": [1, 15]}\n]");
+
+        assert!(report("12x4", Method::Scan, 2, OutputFormat::Table).is_err());
+    }
+
+    #[test]
+    fn add_decimal_carries() {
+        let mut total = vec![];
+        add_decimal(&mut total, "999");
+        add_decimal(&mut total, "1");
+        add_decimal(&mut total, "25");
+        assert_eq!(total, vec![5, 2, 0, 1]);
+    }
+
+    // Lots of random banks, every method has to pick the same batteries as
+    // the original scan, and the batteries have to spell out the joltage
+    #[test]
+    fn methods_agree_on_random_banks() {
+        cases(0x9e3779b97f4a7c15, 1000, |rng| {
+            let length = rng.below(15) as usize + 1;
+            let len = rng.below(30) as usize + length;
+            let digits: Vec<u8> = (0..len).map(|_| rng.below(10) as u8).collect();
+            let expected = max_subsequence_number(&digits, length);
+
+            assert_eq!(expected.indexes.len(), length);
+            assert!(expected.indexes.windows(2).all(|w| w[0] < w[1]));
+            let spelled: String = expected.indexes.iter().map(|&idx| (b'0' + digits[idx]) as char).collect();
+            assert_eq!(spelled, expected.value);
+
+            for method in Method::ALL {
+                assert_eq!(method.select(&digits, length), expected, "{:?} on {:?}", method, digits);
+            }
+        });
+    }
+}
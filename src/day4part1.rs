@@ -1,3 +1,9 @@
+use crate::answer::Answer;
+use crate::error::ParseError;
+use crate::grid::Grid;
+use crate::neighborhood::{Neighborhood, Rules};
+use crate::solver::Solver;
+
 // I have a distinct memory of doing AoC problems like this in the past
 // I'm pretty sure I implemented solutions that reads the input into a
 // 2-d array, then did something like walking the array and checking 
@@ -25,162 +31,79 @@
 // - Every other way I can think of doing this is more work than just
 //   walking the matrix and checking each adjascent space for an @.
 //   I guess I'll go with that and see what happens.
+//
+// Update: "fewer than 4 of the 8 neighbors" isn't hard coded anymore.
+// The Matrix carries a set of Rules (see neighborhood.rs) that say which
+// cells are neighbors, what the threshold is, and whether the edges wrap.
+// Matrix::new still uses the puzzle's rules.
 
-// Struct to hold the matrix and 
+// Struct to hold the matrix and the rules for who can get at a roll.
+// Part 2 uses this one too, so the fields are open to the rest of the crate.
 pub struct Matrix {
-    data: Vec<Vec<usize>>,
-    width: usize,
-    height: usize,
-    accessible_tp_count: usize,
+    pub(crate) data: Grid<usize>,
+    pub(crate) neighborhood: Neighborhood,
 }
 
 impl Matrix {
-    // Read the input into a 2d array (or in this case a vec of vecs)
-    // And at the same time convert the chars to 0 or 1 for simplicity 
-    // Also save height and width as I'll need those for math later
-    pub fn new(content: &str) -> Self {
-        let lines = content.lines();
-        let matrix: Vec<Vec<usize>> = lines
-            .map( |line| 
-                line.chars().map( |c| {
-                    match c {
-                        '.' => 0,
-                        '@' => 1,
-                        _ => panic!("input must only contain . or @")
-                }})
-                .collect()
-            )
-            .collect();
-
-        let height = matrix.len();
-        let width = matrix[0].len();
-
-        Matrix{
-            data: matrix,
-            width,
-            height,
-            accessible_tp_count: 0,
+    // Read the input into a grid and at the same time convert the chars
+    // to 0 or 1 for simplicity. The grid keeps track of the height and
+    // width for me, and makes sure every row is as wide as the first one.
+    pub fn new(content: &str) -> Result<Self, ParseError> {
+        Matrix::with_rules(content, &Rules::puzzle())
+    }
+
+    // Same as new, but deciding who can get at a roll with some other rules
+    pub fn with_rules(content: &str, rules: &Rules) -> Result<Self, ParseError> {
+        let data = Grid::parse(content, |c| match c {
+            '.' => Ok(0),
+            '@' => Ok(1),
+            _ => Err("input must only contain . or @"),
+        })?;
+
+        if data.is_empty() {
+            return Err(ParseError::new(1, 1, content, "input must have at least one row"));
         }
+
+        Ok(Matrix{
+            data,
+            neighborhood: rules.neighborhood(),
+        })
     }
 
     // Just a little helper to print the matrix nice and pretty
+    // and more like the input so it is easier to compare to the
+    // example in case I mess it up.
     pub fn print(&self) {
-        for line in &self.data {
-            println!("{:?}", line)
-        }
+        let rendered = self.data.map(|val| match *val {
+            0 => '.',
+            1 => '@',
+            _ => panic!("Woah, how'd that get in there!?")
+        });
+        println!("{}\n", rendered)
     }
 
     // Main execution for the solution.
-    // It iterates over each item in the matrix, calls tp_neighbor count if the 
-    // current space is a @ to see how many TPs are around it, and if it is 
-    // fewer than 4, it increments the count for the solution. That is what
-    // is returned
-    pub fn count_accessible_rolls(&mut self) -> usize {
-
-        // Iterate over the matrix
-        for (i, row) in self.data.clone().iter().enumerate() {
-            for (j, val) in row.iter().enumerate() {
-
-                // For each cell that has TP in it, check its neighbors
-                if *val == 1 {
-                    let tp_neighbor_count = self.tp_neighbor_count(i, j);
-
-                    // if it has fewer than 4 let's count it!
-                    if tp_neighbor_count < 4 {
-                        self.accessible_tp_count += 1;
-                    }
-                }
-            }
-        }
-
-        self.accessible_tp_count
-    }
-
-    /// Given a a cell in the matrix (identified by a row index and a col index 
-    /// aka an x and y position), count and return the sum of all of the 
-    /// surrounding rolls. First call the neighbors helper which returns 
-    /// the coordinates of all valid neighbors, then , because I change the data
-    /// in the matrix from * and @ to 0 and 1, I can just look up those values
-    /// at each coordinate in the matrix and sum them!
-    fn tp_neighbor_count(&self, row: usize, col: usize) -> usize {
-        let neighbors = self.neighbors(row, col);
-        neighbors.into_iter().map(|(x,y)| self.data[x][y]).sum()
-    } 
-
-    /// Returns a Vec of (row, col) tuples representing all of the neighbors
-    /// of this cell. The implementation is a little silly because the indexes
-    /// are of type usize which can't be negative. So when subtracting, I need
-    /// to use an if let along with checked_sub which returns None if subtracting
-    /// leads to an underflow. When adding, I can just do the normal check to see
-    /// if it is out of bounds based on the width and height of the matrix. 
-    fn neighbors(&self, row: usize, col: usize) -> Vec<(usize, usize)> {
-
-        // A vec to hold all of the valid neighbor coordinates that we 
-        // will later return 
-        let mut output: Vec<(usize, usize)> = vec![];
-
-        // North
-        if let Some(new_row) = row.checked_sub(1) {
-            output.push((new_row, col));
-        }
-        
-        // Northeast
-        if let Some(new_row) = row.checked_sub(1) {
-            if col + 1 < self.width {
-                output.push((new_row, col + 1));
-            }      
-        }
-
-        // East
-        if col + 1 < self.width {
-            output.push((row, col + 1));
-        }
-
-        // Southeast
-        if row + 1 < self.height {
-            if col + 1 < self.width {
-                output.push((row + 1, col + 1));
-            }
-        }
-
-        // South
-        if row + 1 < self.height {
-            output.push((row + 1, col));
-        }
-
-        // Southwest
-        if row + 1 < self.height {
-            if let Some(new_col) = col.checked_sub(1) {
-                output.push((row + 1, new_col));
-            }
-        }
-
-        // West
-        if let Some(new_col) = col.checked_sub(1) {
-            output.push((row, new_col));
-        }
-
-        // Northwest
-        if let Some(new_row) = row.checked_sub(1) {
-            if let Some(new_col) = col.checked_sub(1) {
-                output.push((new_row, new_col));
-            }      
-        }
-
-        output
+    // It used to iterate over each item in the matrix, count the TPs around
+    // every @, and add up the ones with fewer than 4. Update: that walk is
+    // Neighborhood::accessible in neighborhood.rs now, so part 1, part 2,
+    // and every other version of the rules all find rolls the same way.
+    // Which cells are neighbors and what "fewer than 4" means both come
+    // from the rules the matrix was made with.
+    pub fn count_accessible_rolls(&self) -> usize {
+        self.neighborhood.accessible(&self.data).len()
     }
 }
 
 
 
-pub fn solve(content: &str) {
+pub fn solve(content: &str) -> Result<Answer, ParseError> {
 
     // Set up the problem be reading in the data
     // This ends up converting the matrix from a string of *s and @s to
     // a 2d matrix of 0s and 1s. It also derives and saves the height
     // and width of the matrix for use later. See pub fn new in
     // the impl for Matrix.
-    let mut matrix = Matrix::new(content);
+    let matrix = Matrix::new(content)?;
 
     // check the array to make sure I ingested it properly
     // matrix.print();
@@ -188,5 +111,115 @@ pub fn solve(content: &str) {
     // Follow this call chain for the solution!
     let solution = matrix.count_accessible_rolls();
 
-    println!("solution: {}", solution);
-}
\ No newline at end of file
+    Ok(solution.into())
+}
+
+/// solve with some other rules for who can get at a roll, for
+/// `--shape`, `--threshold`, and friends
+pub fn solve_with(content: &str, rules: &Rules) -> Result<Answer, ParseError> {
+    let matrix = Matrix::with_rules(content, rules)?;
+    Ok(matrix.count_accessible_rolls().into())
+}
+
+/// Part 1 with whichever rules for who can get at a roll.
+/// Solution::default() uses the puzzle's.
+#[derive(Default)]
+pub struct Solution {
+    pub rules: Rules,
+}
+
+impl Solver for Solution {
+    fn day(&self) -> u8 {
+        4
+    }
+
+    fn part(&self) -> u8 {
+        1
+    }
+
+    fn name(&self) -> &'static str {
+        "accessible paper rolls"
+    }
+
+    fn parse(&self, content: &str) -> Option<Result<(), ParseError>> {
+        Some(Matrix::with_rules(content, &self.rules).map(|_| ()))
+    }
+
+    fn solve(&self, content: &str) -> Result<Answer, ParseError> {
+        solve_with(content, &self.rules)
+    }
+
+    fn with_rules(&self, rules: &Rules) -> Option<Box<dyn Solver>> {
+        Some(Box::new(Solution { rules: rules.clone() }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sorted(neighbors: impl Iterator<Item = (usize, usize)>) -> Vec<(usize, usize)> {
+        let mut neighbors: Vec<_> = neighbors.collect();
+        neighbors.sort();
+        neighbors
+    }
+
+    #[test]
+    fn neighbors_in_the_middle() {
+        let matrix = Matrix::new("...\n.@.\n...").unwrap();
+        assert_eq!(
+            sorted(matrix.data.neighbors8(1, 1)),
+            vec![(0, 0), (0, 1), (0, 2), (1, 0), (1, 2), (2, 0), (2, 1), (2, 2)]
+        );
+    }
+
+    #[test]
+    fn neighbors_in_the_corners() {
+        let matrix = Matrix::new("...\n...\n...").unwrap();
+        assert_eq!(sorted(matrix.data.neighbors8(0, 0)), vec![(0, 1), (1, 0), (1, 1)]);
+        assert_eq!(sorted(matrix.data.neighbors8(0, 2)), vec![(0, 1), (1, 1), (1, 2)]);
+        assert_eq!(sorted(matrix.data.neighbors8(2, 0)), vec![(1, 0), (1, 1), (2, 1)]);
+        assert_eq!(sorted(matrix.data.neighbors8(2, 2)), vec![(1, 1), (1, 2), (2, 1)]);
+    }
+
+    #[test]
+    fn neighbors_on_the_edges() {
+        let matrix = Matrix::new("....\n....\n....").unwrap();
+        assert_eq!(sorted(matrix.data.neighbors8(0, 1)), vec![(0, 0), (0, 2), (1, 0), (1, 1), (1, 2)]);
+        assert_eq!(sorted(matrix.data.neighbors8(1, 3)), vec![(0, 2), (0, 3), (1, 2), (2, 2), (2, 3)]);
+    }
+
+    #[test]
+    fn neighbors_of_a_single_cell() {
+        let matrix = Matrix::new("@").unwrap();
+        assert_eq!(matrix.data.neighbors8(0, 0).count(), 0);
+    }
+
+    #[test]
+    fn roll_count() {
+        let matrix = Matrix::new("@@.\n@@@\n..@").unwrap();
+        assert_eq!(matrix.neighborhood.roll_count(&matrix.data, 1, 1), 5);
+        assert_eq!(matrix.neighborhood.roll_count(&matrix.data, 0, 0), 3);
+        assert_eq!(matrix.neighborhood.roll_count(&matrix.data, 2, 0), 2);
+    }
+
+    #[test]
+    fn other_rules() {
+        use crate::neighborhood::{Edges, Shape};
+
+        let content = "@@.\n@@@\n..@";
+        let edges_only = Rules::new(Shape::VonNeumann(1), 3, Edges::Bounded);
+        let matrix = Matrix::with_rules(content, &edges_only).unwrap();
+        assert_eq!(matrix.neighborhood.roll_count(&matrix.data, 1, 1), 3);
+        // every roll but the middle one shares an edge with 2 or fewer rolls
+        assert_eq!(Matrix::with_rules(content, &edges_only).unwrap().count_accessible_rolls(), 5);
+        // wrapping round, every cell of a 3x3 grid is next to every other one
+        assert_eq!(Matrix::with_rules(content, &Rules::new(Shape::Moore(1), 4, Edges::Wrap)).unwrap().count_accessible_rolls(), 0);
+    }
+
+    #[test]
+    fn new_rejects_other_characters() {
+        let e = Matrix::new("..@\n.x.").err().unwrap();
+        assert_eq!((e.line, e.column, e.text.as_str()), (2, 2, "x"));
+    }
+}
@@ -1,71 +1,376 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::RangeInclusive;
+
+use crate::answer::Answer;
+use crate::day5part1::parse_input;
+use crate::error::ParseError;
+use crate::solver::{OutputFormat, ReportOptions, Solver};
+
 // Let's break this down a little and write a little BST
 // just to help me remember how to do it.
+//
+// It started out as a tree of i32s that could only insert and look things
+// up, and solve just inserted a few hard coded numbers. Now it holds any
+// Ord type, can remove things, walk itself in order, pre order, and post
+// order, answer range queries, and optionally keep itself balanced. Solve
+// loads the real day 5 ingredient IDs so there's something to look at.
 
-struct Node {
-    val: i32,
-    left: Option<Box<Node>>,
-    right: Option<Box<Node>>,
+struct Node<T> {
+    val: T,
+    // height of the subtree rooted here, a leaf is 1
+    height: usize,
+    left: Option<Box<Node<T>>>,
+    right: Option<Box<Node<T>>>,
 }
 
-impl Node {
-    fn to_string(&self) -> String {
-        return format!("{}", self.val.to_string());
+impl<T: fmt::Display> fmt::Display for Node<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.val)
     }
 }
 
 // Define a from to make Node creation simpler
-impl From<i32> for Box<Node> {
-    fn from(val: i32) -> Self {
-        Box::new(Node { val, left: None, right: None })
+impl<T> From<T> for Box<Node<T>> {
+    fn from(val: T) -> Self {
+        Box::new(Node { val, height: 1, left: None, right: None })
     }
 }
 
+type Link<T> = Option<Box<Node<T>>>;
 
-struct Tree {
-    root: Option<Box<Node>>,
+fn height<T>(link: &Link<T>) -> usize {
+    link.as_ref().map(|node| node.height).unwrap_or(0)
 }
 
-impl Tree {
-    pub fn insert(&mut self, val: i32) {
+fn update<T>(node: &mut Node<T>) {
+    node.height = 1 + height(&node.left).max(height(&node.right));
+}
 
-        let mut node = &mut self.root;
+// These are the same AVL rotations as interval_tree.rs. When the tree is
+// in balanced mode, every node on the way back up from an insert or remove
+// gets rotated if one side has gotten 2 taller than the other.
+fn rotate_right<T>(mut node: Box<Node<T>>) -> Box<Node<T>> {
+    let mut left = node.left.take().expect("rotate_right needs a left child");
+    node.left = left.right.take();
+    update(&mut node);
+    left.right = Some(node);
+    update(&mut left);
+    left
+}
 
-        while let Some(cur) = node {
-            if val <= cur.val {
-                node = &mut cur.left;
-            } else {
-                node = &mut cur.right;
+fn rotate_left<T>(mut node: Box<Node<T>>) -> Box<Node<T>> {
+    let mut right = node.right.take().expect("rotate_left needs a right child");
+    node.right = right.left.take();
+    update(&mut node);
+    right.left = Some(node);
+    update(&mut right);
+    right
+}
+
+/// Fix the node's height and, if balancing is on, rotate it back into shape
+fn fix<T>(mut node: Box<Node<T>>, balanced: bool) -> Box<Node<T>> {
+    update(&mut node);
+    if !balanced {
+        return node;
+    }
+
+    let balance = |node: &Node<T>| height(&node.left) as isize - height(&node.right) as isize;
+
+    match balance(&node) {
+        2 => {
+            if balance(node.left.as_ref().unwrap()) < 0 {
+                node.left = Some(rotate_left(node.left.take().unwrap()));
             }
+            rotate_right(node)
+        }
+        -2 => {
+            if balance(node.right.as_ref().unwrap()) > 0 {
+                node.right = Some(rotate_right(node.right.take().unwrap()));
+            }
+            rotate_left(node)
+        }
+        _ => node,
+    }
+}
+
+fn insert_node<T: Ord>(link: Link<T>, val: T, balanced: bool) -> Box<Node<T>> {
+    let Some(mut cur) = link else {
+        return val.into();
+    };
+
+    if val <= cur.val {
+        cur.left = Some(insert_node(cur.left.take(), val, balanced));
+    } else {
+        cur.right = Some(insert_node(cur.right.take(), val, balanced));
+    }
+
+    fix(cur, balanced)
+}
+
+/// Pull the smallest node out of a subtree, returning what's left of the
+/// subtree along with the node
+fn remove_min<T>(mut cur: Box<Node<T>>, balanced: bool) -> (Link<T>, Box<Node<T>>) {
+    match cur.left.take() {
+        None => (cur.right.take(), cur),
+        Some(left) => {
+            let (rest, min) = remove_min(left, balanced);
+            cur.left = rest;
+            (Some(fix(cur, balanced)), min)
+        }
+    }
+}
+
+/// Remove one node holding val. The bool is whether anything was removed.
+fn remove_node<T: Ord>(link: Link<T>, val: &T, balanced: bool) -> (Link<T>, bool) {
+    let Some(mut cur) = link else {
+        return (None, false);
+    };
+
+    let removed = match val.cmp(&cur.val) {
+        Ordering::Less => {
+            let (left, removed) = remove_node(cur.left.take(), val, balanced);
+            cur.left = left;
+            removed
+        }
+        Ordering::Greater => {
+            let (right, removed) = remove_node(cur.right.take(), val, balanced);
+            cur.right = right;
+            removed
+        }
+        Ordering::Equal => {
+            // The usual three cases. No children or one child, the child
+            // (or nothing) takes this node's spot. Two children, the
+            // smallest thing on the right side takes its spot.
+            let replacement = match (cur.left.take(), cur.right.take()) {
+                (None, child) | (child, None) => child,
+                (Some(left), Some(right)) => {
+                    let (rest, mut successor) = remove_min(right, balanced);
+                    successor.left = Some(left);
+                    successor.right = rest;
+                    Some(fix(successor, balanced))
+                }
+            };
+            return (replacement, true);
         }
-        *node = Some(val.into());
+    };
+
+    (Some(fix(cur, balanced)), removed)
+}
+
+pub struct Tree<T> {
+    root: Link<T>,
+    // keep the tree balanced (AVL style) on every insert and remove
+    balanced: bool,
+    len: usize,
+}
+
+impl<T: Ord> Tree<T> {
+    /// A plain BST. Insert things in sorted order and it turns into a
+    /// linked list, which is kind of the point of having it around.
+    pub fn new() -> Self {
+        Tree { root: None, balanced: false, len: 0 }
+    }
+
+    /// A BST that rebalances itself so it stays O(log n) tall
+    pub fn balanced() -> Self {
+        Tree { root: None, balanced: true, len: 0 }
+    }
+
+    pub fn insert(&mut self, val: T) {
+        self.root = Some(insert_node(self.root.take(), val, self.balanced));
+        self.len += 1;
+    }
+
+    /// Remove one copy of val from the tree. Returns true if it was there.
+    pub fn remove(&mut self, val: &T) -> bool {
+        let (root, removed) = remove_node(self.root.take(), val, self.balanced);
+        self.root = root;
+        if removed {
+            self.len -= 1;
+        }
+        removed
     }
 
     // Returns true if the value is in the tree and false otherwise.
-    pub fn is_in_tree(&mut self, val: i32) -> bool {
-        let mut node = &mut self.root;
+    // This used to take &mut self for no good reason, looking doesn't
+    // change anything.
+    pub fn is_in_tree(&self, val: &T) -> bool {
+        let mut node = &self.root;
 
         while let Some(cur) = node {
-            if val == cur.val {
-                return true
+            match val.cmp(&cur.val) {
+                Ordering::Equal => return true,
+                Ordering::Less => node = &cur.left,
+                Ordering::Greater => node = &cur.right,
             }
+        }
 
-            if val < cur.val {
-                node = &mut cur.left;
-            } else {
-                node = &mut cur.right;
-            }
+        false
+    }
+
+    /// How many values are in the tree, counting duplicates
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    /// How many levels the tree has, 0 when it's empty
+    pub fn height(&self) -> usize {
+        height(&self.root)
+    }
+
+    /// Smallest value, all the way down the left side
+    pub fn min(&self) -> Option<&T> {
+        let mut cur = self.root.as_ref()?;
+        while let Some(left) = &cur.left {
+            cur = left;
+        }
+        Some(&cur.val)
+    }
+
+    /// Biggest value, all the way down the right side
+    pub fn max(&self) -> Option<&T> {
+        let mut cur = self.root.as_ref()?;
+        while let Some(right) = &cur.right {
+            cur = right;
         }
+        Some(&cur.val)
+    }
+
+    /// Every value between range.start() and range.end() (inclusive) in
+    /// sorted order. Subtrees that are entirely outside the range get
+    /// skipped instead of walked.
+    pub fn range(&self, range: RangeInclusive<T>) -> Vec<&T> {
+        let mut found = vec![];
+        range_search(&self.root, &range, &mut found);
+        found
+    }
+
+    /// Left, node, right, which for a BST is sorted order
+    pub fn in_order(&self) -> InOrder<'_, T> {
+        let mut iter = InOrder { stack: vec![] };
+        iter.push_left(&self.root);
+        iter
+    }
+
+    /// Node, left, right
+    pub fn pre_order(&self) -> PreOrder<'_, T> {
+        PreOrder { stack: self.root.as_deref().into_iter().collect() }
+    }
+
+    /// Left, right, node
+    pub fn post_order(&self) -> PostOrder<'_, T> {
+        PostOrder { stack: self.root.as_deref().map(|node| (node, false)).into_iter().collect() }
+    }
+}
+
+impl<T: Ord> Default for Tree<T> {
+    fn default() -> Self {
+        Tree::new()
+    }
+}
+
+// Duplicates can end up on either side of each other once rotations get
+// involved, so this goes left and right on ties rather than picking one.
+fn range_search<'a, T: Ord>(link: &'a Link<T>, range: &RangeInclusive<T>, found: &mut Vec<&'a T>) {
+    let Some(cur) = link else {
+        return;
+    };
+
+    if *range.start() <= cur.val {
+        range_search(&cur.left, range, found);
+    }
+    if range.contains(&cur.val) {
+        found.push(&cur.val);
+    }
+    if *range.end() >= cur.val {
+        range_search(&cur.right, range, found);
+    }
+}
+
+/// In order iterator. The stack holds nodes that we've gone left past but
+/// haven't visited yet.
+pub struct InOrder<'a, T> {
+    stack: Vec<&'a Node<T>>,
+}
+
+impl<'a, T> InOrder<'a, T> {
+    fn push_left(&mut self, mut link: &'a Link<T>) {
+        while let Some(node) = link {
+            self.stack.push(node);
+            link = &node.left;
+        }
+    }
+}
+
+impl<'a, T> Iterator for InOrder<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.stack.pop()?;
+        self.push_left(&node.right);
+        Some(&node.val)
+    }
+}
+
+/// Pre order iterator. Push right before left so left comes off first.
+pub struct PreOrder<'a, T> {
+    stack: Vec<&'a Node<T>>,
+}
+
+impl<'a, T> Iterator for PreOrder<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.stack.pop()?;
+        self.stack.extend(node.right.as_deref());
+        self.stack.extend(node.left.as_deref());
+        Some(&node.val)
+    }
+}
+
+/// Post order iterator. Each node goes on the stack twice, the first time
+/// to push its children on top of it and the second time to be visited.
+pub struct PostOrder<'a, T> {
+    stack: Vec<(&'a Node<T>, bool)>,
+}
 
-        return false
+impl<'a, T> Iterator for PostOrder<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((node, children_done)) = self.stack.pop() {
+            if children_done {
+                return Some(&node.val);
+            }
+            self.stack.push((node, true));
+            self.stack.extend(node.right.as_deref().map(|right| (right, false)));
+            self.stack.extend(node.left.as_deref().map(|left| (left, false)));
+        }
+        None
     }
+}
 
+impl<T: fmt::Display> Tree<T> {
     pub fn print(&self) {
+        println!("{}", self.render());
+    }
+
+    /// The tree drawn sideways, the way print shows it (see the picture
+    /// above recursive_render)
+    pub fn render(&self) -> String {
         let mut path = vec![];
+        let mut lines = vec![];
         if let Some(node) = &self.root {
-            self.recursive_print(&node, 0, "", &mut path);
+            self.recursive_render(node, 0, "", &mut path, &mut lines);
         } else {
-            println!("Tree is empty");
+            lines.push("Tree is empty".to_string());
         }
+        lines.join("\n")
     }
 
     // The goal here is to recursively scan the tree printing at the right time
@@ -80,15 +385,19 @@ impl Tree {
     // │   ┌──4
     // └──3
     //    └──3
-    fn recursive_print(&self, node: &Box<Node>, depth: usize, from_direction: &str, path: &mut Vec<char>){
+    //
+    // Update: this used to println! each line as it went, which meant solve
+    // was drawing trees in the middle of run and bench output. Now the lines
+    // get collected and render hands them back so --report can show them.
+    fn recursive_render(&self, node: &Node<T>, depth: usize, from_direction: &str, path: &mut Vec<char>, lines: &mut Vec<String>){
         // if there is right go right
         if let Some(right_node) = &node.right {
             path.push('R');
-            self.recursive_print(&right_node, depth + 1, "right", path);
+            self.recursive_render(right_node, depth + 1, "right", path, lines);
         }
-       
+
         if depth == 0{
-            println!("{}", node.val.to_string());
+            lines.push(node.val.to_string());
         } else {
 
             let mut prefix: Vec<String> = vec![];
@@ -112,38 +421,240 @@ impl Tree {
                 _ => ""
             };
 
-            println!("{}{}{}{}", prefix.join("").to_string(), elbow, "──", node.val.to_string());
+            lines.push(format!("{}{}──{}", prefix.join(""), elbow, node.val));
         }
 
         // if there is left go left
         if let Some(left_node) = &node.left {
             path.push('L');
-            self.recursive_print(&left_node, depth + 1, "left", path);
+            self.recursive_render(left_node, depth + 1, "left", path, lines);
         }
         path.pop();
     }
 
+    /// Every value in order along with how deep it is (the root is 0)
+    pub fn depths(&self) -> Vec<(&T, usize)> {
+        fn walk<'a, T>(link: &'a Link<T>, depth: usize, out: &mut Vec<(&'a T, usize)>) {
+            if let Some(node) = link {
+                walk(&node.left, depth + 1, out);
+                out.push((&node.val, depth));
+                walk(&node.right, depth + 1, out);
+            }
+        }
+
+        let mut out = vec![];
+        walk(&self.root, 0, &mut out);
+        out
+    }
+}
+
+/// The plain tree and the balanced one, both holding every ID in the input
+fn build_trees(content: &str) -> Result<(Tree<usize>, Tree<usize>), ParseError> {
+    let (_, ids) = parse_input(content)?;
+
+    let mut tree = Tree::new();
+    let mut balanced_tree = Tree::balanced();
+    for id in ids {
+        tree.insert(id);
+        balanced_tree.insert(id);
+    }
+
+    Ok((tree, balanced_tree))
+}
+
+pub fn solve(content: &str) -> Result<Answer, ParseError> {
+    // put the same IDs in a plain tree and a balanced one to see how
+    // much of a difference balancing makes to the height
+    let (tree, balanced_tree) = build_trees(content)?;
+
+    let (Some(min), Some(max)) = (tree.min(), tree.max()) else {
+        return Ok(Answer::Text("no IDs in the tree".to_string()));
+    };
+
+    Ok(Answer::Text(format!(
+        "{} IDs from {} to {}, height {} plain and {} balanced",
+        tree.len(),
+        min,
+        max,
+        tree.height(),
+        balanced_tree.height()
+    )))
 }
 
+/// The plain tree for --report. The table is the drawing from print, csv
+/// and json list the IDs in order with how deep each one sits, for trees
+/// too big to look at.
+pub fn report(content: &str, format: OutputFormat) -> Result<String, ParseError> {
+    let (tree, _) = build_trees(content)?;
+    let depths = tree.depths();
+
+    Ok(match format {
+        OutputFormat::Table => tree.render(),
+        OutputFormat::Csv => {
+            let rows = depths.iter().map(|(id, depth)| format!("{},{}", id, depth));
+            std::iter::once("id,depth".to_string()).chain(rows).collect::<Vec<String>>().join("\n")
+        }
+        OutputFormat::Json => {
+            let entries: Vec<String> = depths.iter().map(|(id, depth)| format!("  {{\"id\": {}, \"depth\": {}}}", id, depth)).collect();
+            format!("[\n{}\n]", entries.join(",\n"))
+        }
+    })
+}
 
-pub fn solve(_content: &str) {
-    let mut tree = Tree{root: None};
-    tree.print();
+pub struct Solution;
 
-    tree.insert(5);
-    tree.insert(3);
-    tree.insert(11);
-    tree.insert(15);
-    tree.insert(3);
-    tree.insert(7);
-    tree.insert(4);
-    tree.insert(9);
-    tree.insert(10);
-    tree.insert(20);
+impl Solver for Solution {
+    fn day(&self) -> u8 {
+        5
+    }
 
+    fn part(&self) -> u8 {
+        1
+    }
 
-    println!("find 8, which is not in the tree: {:?}", tree.is_in_tree(8));
-    println!("find 9, which IS in the tree: {:?}", tree.is_in_tree(9));
+    fn name(&self) -> &'static str {
+        "BST of the ingredient IDs"
+    }
 
-    tree.print();
+    fn variant(&self) -> Option<&'static str> {
+        Some("bst")
+    }
+
+    fn parse(&self, content: &str) -> Option<Result<(), ParseError>> {
+        Some(parse_input(content).map(|_| ()))
+    }
+
+    fn solve(&self, content: &str) -> Result<Answer, ParseError> {
+        solve(content)
+    }
+
+    fn report(&self, content: &str, options: ReportOptions) -> Option<Result<String, ParseError>> {
+        Some(report(content, options.format))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // the tree from the comment above recursive_render
+    fn example(tree: &mut Tree<i32>) {
+        for val in [5, 3, 11, 15, 3, 7, 4, 9, 10, 20] {
+            tree.insert(val);
+        }
+    }
+
+    fn check_heights<T>(link: &Link<T>, balanced: bool) -> usize {
+        let Some(node) = link else {
+            return 0;
+        };
+        let left = check_heights(&node.left, balanced);
+        let right = check_heights(&node.right, balanced);
+        assert_eq!(node.height, 1 + left.max(right));
+        if balanced {
+            assert!(left.abs_diff(right) <= 1);
+        }
+        node.height
+    }
+
+    #[test]
+    fn is_in_tree() {
+        let mut tree = Tree::new();
+        example(&mut tree);
+        assert!(!tree.is_in_tree(&8));
+        assert!(tree.is_in_tree(&9));
+    }
+
+    #[test]
+    fn traversals() {
+        let mut tree = Tree::new();
+        example(&mut tree);
+        assert_eq!(tree.in_order().copied().collect::<Vec<_>>(), vec![3, 3, 4, 5, 7, 9, 10, 11, 15, 20]);
+        assert_eq!(tree.pre_order().copied().collect::<Vec<_>>(), vec![5, 3, 3, 4, 11, 7, 9, 10, 15, 20]);
+        assert_eq!(tree.post_order().copied().collect::<Vec<_>>(), vec![3, 4, 3, 10, 9, 7, 20, 15, 11, 5]);
+        assert_eq!(Tree::<i32>::new().post_order().count(), 0);
+    }
+
+    #[test]
+    fn len_height_min_max() {
+        let mut tree = Tree::new();
+        assert_eq!((tree.len(), tree.height(), tree.min(), tree.max()), (0, 0, None, None));
+
+        example(&mut tree);
+        assert_eq!((tree.len(), tree.height(), tree.min(), tree.max()), (10, 5, Some(&3), Some(&20)));
+    }
+
+    #[test]
+    fn range() {
+        let mut tree = Tree::new();
+        example(&mut tree);
+        assert_eq!(tree.range(4..=10), vec![&4, &5, &7, &9, &10]);
+        assert_eq!(tree.range(3..=3), vec![&3, &3]);
+        assert!(tree.range(12..=14).is_empty());
+    }
+
+    #[test]
+    fn remove() {
+        for mut tree in [Tree::new(), Tree::balanced()] {
+            example(&mut tree);
+
+            assert!(tree.remove(&5));
+            assert!(tree.remove(&3));
+            assert!(tree.remove(&20));
+            assert!(!tree.remove(&8));
+
+            assert_eq!(tree.len(), 7);
+            assert_eq!(tree.in_order().copied().collect::<Vec<_>>(), vec![3, 4, 7, 9, 10, 11, 15]);
+            check_heights(&tree.root, tree.balanced);
+        }
+    }
+
+    #[test]
+    fn balanced_mode_stays_short() {
+        let mut plain = Tree::new();
+        let mut balanced = Tree::balanced();
+        for val in 0..1000 {
+            plain.insert(val);
+            balanced.insert(val);
+        }
+
+        // sorted inserts turn a plain tree into a linked list
+        assert_eq!(plain.height(), 1000);
+        assert_eq!(balanced.height(), 10);
+        check_heights(&balanced.root, true);
+
+        for val in (0..1000).step_by(3) {
+            assert!(balanced.remove(&val));
+        }
+        check_heights(&balanced.root, true);
+        assert_eq!(balanced.in_order().copied().collect::<Vec<_>>(), (0..1000).filter(|v| v % 3 != 0).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn render_matches_the_picture() {
+        let mut tree = Tree::new();
+        example(&mut tree);
+        let picture = [
+            "      ┌──20",
+            "   ┌──15",
+            "┌──11",
+            "│   │      ┌──10",
+            "│   │   ┌──9",
+            "│   └──7",
+            "5",
+            "│   ┌──4",
+            "└──3",
+            "   └──3",
+        ];
+        assert_eq!(tree.render(), picture.join("\n"));
+        assert_eq!(Tree::<i32>::new().render(), "Tree is empty");
+    }
+
+    #[test]
+    fn report_lists_ids_with_depths() {
+        let content = "3-5\n\n5\n1\n8";
+        assert_eq!(report(content, OutputFormat::Table).unwrap(), "┌──8\n5\n└──1");
+        assert_eq!(report(content, OutputFormat::Csv).unwrap(), "id,depth\n1,1\n5,0\n8,1");
+        assert!(report("3-5\n5", OutputFormat::Csv).is_err());
+    }
 }
@@ -1,4 +1,11 @@
 use std::fmt;
+use std::ops::RangeInclusive;
+
+use crate::answer::Answer;
+use crate::error::ParseError;
+use crate::interval::IntervalSet;
+use crate::interval_tree::IntervalTree;
+use crate::solver::Solver;
 
 // What I really really wanted to do for this is to make a fancy BST
 // where each of the nodes was a range ov numbers. I got a little
@@ -75,9 +82,9 @@ use std::fmt;
 
 // a range is two numbers (that can be the same number)
 // where start <= end
-struct Range {
-    start: usize,
-    end: usize,
+pub struct Range {
+    pub start: usize,
+    pub end: usize,
 }
 
 // Pretty printing for Range
@@ -96,236 +103,256 @@ impl fmt::Debug for Range {
     }
 }
 
-// Easily convert a string in the format "1-5" to a Range with start and end
-impl From<&str> for Range {
-    fn from(val: &str) -> Self {
-        let split: Vec<&str> = val.split("-").into_iter().collect::<Vec<&str>>();
-        
+// Convert a string in the format "1-5" to a Range with start and end.
+// The line number is only there so that errors can say where the bad
+// range was in the input.
+impl Range {
+    pub fn parse(line_number: usize, val: &str) -> Result<Self, ParseError> {
+        let split: Vec<&str> = val.split("-").collect::<Vec<&str>>();
+
         if split.len() != 2 {
-            panic!("a range can only be made from a string like xx-yy")
+            return Err(ParseError::new(line_number, 1, val, "a range can only be made from a string like xx-yy"));
         }
-    
-        let results: Vec<usize> = split.iter().map(|s| s.parse::<usize>().unwrap()).collect::<Vec<usize>>();
+
+        let mut results: Vec<usize> = vec![];
+        let mut column = 1;
+        for s in split {
+            let Ok(num) = s.parse::<usize>() else {
+                return Err(ParseError::new(line_number, column, s, "range bounds must be whole numbers"));
+            };
+            results.push(num);
+            column += s.len() + 1;
+        }
+
         let min = results.iter().min().unwrap();
         let max = results.iter().max().unwrap();
 
-        Range{start: *min, end: *max}
+        Ok(Range{start: *min, end: *max})
     }
 }
 
-// A collection of non-overlapping Ranges that implements range merging on insert.
-struct RangeList {
-    ranges: Vec<Range>
+// All of those cases used to be handled by a RangeList here (and a copy
+// of it in part 2) with a lot of index juggling. That grew up into
+// IntervalSet over in interval.rs, which merges on insert the same way
+// and can do a binary search for contains. The fancy range BST finally
+// exists too, as IntervalTree in interval_tree.rs, so both parts can be
+// run with either one (the tree versions are 5-1-avl and 5-2-avl) and
+// `bench` can say which is actually faster.
+
+/// Somewhere to keep the fresh ranges. This is just the bit that both
+/// IntervalSet and IntervalTree can do, so the solvers don't care which
+/// one they get.
+pub trait FreshRanges: FromIterator<RangeInclusive<usize>> {
+    fn is_fresh(&self, id: usize) -> bool;
+    fn fresh_count(&self) -> u128;
 }
 
-impl RangeList {
-
-    // Inserting values into the list should handle merges
-    // so that by the time insert is finished, the RangeList 
-    // has only mutually exclusive ranges. At rest, ranges
-    // should be sorted by the start value.
-    // e.g. existing list [1-3, 4-6, 7-9, 11-13] and we add 5-12
-    // First we position the new item
-    // [1-3, 4-6, 7-9, 11-13]
-    //           ^
-    //           5-12
-    //
-    // there is overlap with previous item, so the end of that gets updated
-    // and we move cur back one so it is like we just inserted this new range
-    // list now looks like this
-    // [1-3, 4-12, 7-9, 11-13]
-    //       ^
-    //       cur_idx
-    // 
-    // we check the number in front of it and see that there is full overlap
-    // so we remove that number in the list and see that there is partial overlap
-    // so we update cur and remove the next number
-    // [1-3, 4-12, 11-13]
-    //       ^
-    //       cur_idx
-    // 
-    // [1-3, 4-13, 11-13]
-    // [1-3, 4-13]
-    //
-    // This method could really use some optimizing. I know there is a way
-    // to make this more elegant, but I haven't thought of it yet
-    pub fn insert(&mut self, val: &str){
-
-        // For ranges that are mutually exclusive,
-        // Iterate over the list until you find a range start that
-        // is greater than this one, then insert it.
-        let new_range: Range = val.into();
-
-        let mut cur_idx = 0;
-
-        // println!("insert: about to loop, cur_ids: {}, self.ranges.len(): {}", cur_idx, self.ranges.len() );
-        // Scan to get the correct index for inserting.
-        while cur_idx < self.ranges.len() && self.ranges[cur_idx].start < new_range.start {
-            cur_idx += 1;
-        }
+impl FreshRanges for IntervalSet<usize> {
+    fn is_fresh(&self, id: usize) -> bool {
+        self.contains(id)
+    }
 
-        // println!("insert: Ended initial range scan on index: {}", cur_idx);
-
-        // if the new items comes after another item, we need to check for overlap
-        if cur_idx != 0 {
-
-            // if prev.end >= new.start - 1 it means there is left overlap
-            // e.g. prev: 2-5 and new: 2-5 or 4-7 or 6-8
-            //  We need the extra - 1 to handle when ranges 
-            // are off by 1 e.g. 2-4 and 5-6 should be combined
-            if self.ranges[cur_idx - 1].end >= new_range.start - 1 {
-
-                // prev.end >= new.end it is complete overlap 
-                // e.g. prev: 2-5 and new: 3-4 or 2-5
-                if self.ranges[cur_idx - 1].end >= new_range.end {
-                    // no other action at all is needed
-                    // we don't need to insert the new range or anything
-                } else {
-
-                    // otherwise prev.end < new.end, so we should update the
-                    // end on prev
-                    // e.g.  prev: 2-5 and new: 2-7
-                    self.ranges[cur_idx - 1].end = new_range.end;
-                    cur_idx -= 1;
-                }
-            } else {
-                // if there is no left overlap, we insert a new item
-                self.ranges.insert(cur_idx, new_range);
-            }
-
-        } else {
-
-            // if the new item we want to insert goes at the beginning, insert it
-            self.ranges.insert(cur_idx, new_range);
-        }
+    fn fresh_count(&self) -> u128 {
+        self.len()
+    }
+}
 
-        // Now we need to check for overlap in the next ranges
-        // If there is right overlap we will remove the item or update to make sure
-        // everything is mutually exclusive.
-        // Only scan to the second to last item, because if it the last item, there 
-        // is nothing in front of it to modify
-
-        // if new.end > next.start and new.end > next.end there is some overlap
-        // extending to the right. We need the extra - 1 to handle when ranges 
-        // are off by 1 e.g. 2-4 and 5-6 should be combined
-        while cur_idx < self.ranges.len() - 1 && self.ranges[cur_idx].end >= self.ranges[cur_idx + 1].start - 1 {
-
-            // if new.end < next.end there is partial overlap
-            // e.g. new: 5-8 and next: 5-9 or 8-10
-            if self.ranges[cur_idx].end < self.ranges[cur_idx + 1].end {
-                // extend the end of the new range
-                self.ranges[cur_idx].end = self.ranges[cur_idx + 1].end
-            }
-
-            // for both partial overlap above and complete overlap 
-            // (e.g. new: 5-8 and next: 4-7 or 5-8)
-            // we will remove the next item in the list
-            self.ranges.remove(cur_idx + 1);
-
-            // This process should repeat until the item we added does not overlap 
-            // the next item OR if the item we added becomes the last item in the list
-        }
+impl FreshRanges for IntervalTree<usize> {
+    fn is_fresh(&self, id: usize) -> bool {
+        self.contains(id)
     }
 
-    /// Scan the list and return true if the number is in a range in the list
-    /// and false otherwise. Even though I don't have a BST, I can still 
-    /// do a binary search! 
-    pub fn find(&self, num: usize) -> bool {
+    fn fresh_count(&self) -> u128 {
+        self.len()
+    }
+}
 
-        // lame boring linear search
-        // for range in &self.ranges {
+/// Split the input into the fresh ranges and the ingredient IDs
+pub fn parse_input(content: &str) -> Result<(Vec<Range>, Vec<usize>), ParseError> {
+    let split_content: Vec<&str> = content.split("\n\n").collect::<Vec<&str>>();
+    if split_content.len() != 2 {
+        return Err(ParseError::new(content.lines().count() + 1, 1, "", "expected the ranges and the IDs to be separated by one blank line"));
+    }
+    let ranges = split_content[0];
+    let inputs = split_content[1];
 
-        //     // break early if we exceed the range 
-        //     if num < range.start {
-        //         return false
-        //     } 
+    let mut parsed_ranges = vec![];
+    for (line_index, range) in ranges.lines().enumerate() {
+        parsed_ranges.push(Range::parse(line_index + 1, range)?);
+    }
+
+    // the IDs start after the ranges and the blank line
+    let first_id_line = ranges.lines().count() + 2;
 
-        //     if num >= range.start && num <= range.end {
-        //         return true
-        //     }
-        // }
+    let mut ids = vec![];
+    for (line_index, num) in inputs.lines().enumerate() {
+        let Ok(id) = num.parse() else {
+            return Err(ParseError::new(first_id_line + line_index, 1, num, "ingredient ID must be a whole number"));
+        };
+        ids.push(id);
+    }
 
-        // return false
+    Ok((parsed_ranges, ids))
+}
 
+/// Count the fresh IDs, keeping the ranges in whichever backend R is
+pub fn count_fresh<R: FreshRanges>(content: &str) -> Result<Answer, ParseError> {
+    let (ranges, ids) = parse_input(content)?;
+    let fresh_ranges: R = ranges.iter().map(|range| range.start..=range.end).collect();
 
-        // Fun exciting binary search!
-        let mut min = 0;
-        let mut max = self.ranges.len();
+    let fresh_count = ids.into_iter().filter(|id| fresh_ranges.is_fresh(*id)).count();
 
-        while min < max {
-            let mid = min + (max - min) / 2;
-            let range = &self.ranges[mid];
+    Ok(fresh_count.into())
+}
 
-            // on each iteration, check to see if this is the range that fits
-            if num >= range.start && num <= range.end {
-                return true
-            }
+pub fn solve(content: &str) -> Result<Answer, ParseError> {
+    count_fresh::<IntervalSet<usize>>(content)
+}
 
-            if num < self.ranges[mid].start {
-                max = mid
-            } else {
-                min = mid + 1
-            }
+pub struct Solution;
 
-            if min == max {
-                return false;
-            }
-        }
+impl Solver for Solution {
+    fn day(&self) -> u8 {
+        5
+    }
 
+    fn part(&self) -> u8 {
+        1
+    }
+
+    fn name(&self) -> &'static str {
+        "fresh ingredient IDs"
+    }
 
-        return false
+    fn parse(&self, content: &str) -> Option<Result<(), ParseError>> {
+        Some(parse_input(content).map(|_| ()))
     }
 
-    pub fn print(&self){
-        println!("RangeList: {:?}", self.ranges);
+    fn solve(&self, content: &str) -> Result<Answer, ParseError> {
+        solve(content)
     }
 }
 
-// The super simplist version is to just put everything
-// into a list that we will scan every time. Let's do that 
-// for now.
-pub fn solve(content: &str) {
+/// Same as Solution but with the ranges in an AVL IntervalTree
+pub struct TreeSolution;
 
-    let split_content: Vec<&str> = content.split("\n\n").collect::<Vec<&str>>();
-    let ranges = split_content[0];
-    let inputs = split_content[1];
-    let mut fresh_count = 0;
-    let mut range_list = RangeList{ranges: vec![]};
+impl Solver for TreeSolution {
+    fn day(&self) -> u8 {
+        5
+    }
 
-    for range in ranges.lines() {
-        range_list.insert(range);
+    fn part(&self) -> u8 {
+        1
     }
 
-    for num in inputs.lines() {
-        if range_list.find(num.parse().unwrap()) {
-            fresh_count += 1;
-        }
+    fn name(&self) -> &'static str {
+        "fresh ingredient IDs with an interval tree"
     }
 
-    println!("fresh count: {:?}", fresh_count);
+    fn variant(&self) -> Option<&'static str> {
+        Some("avl")
+    }
+
+    fn parse(&self, content: &str) -> Option<Result<(), ParseError>> {
+        Some(parse_input(content).map(|_| ()))
+    }
+
+    fn solve(&self, content: &str) -> Result<Answer, ParseError> {
+        count_fresh::<IntervalTree<usize>>(content)
+    }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-// Some tests:
-// let test_mutually_exclusice_str_vec: Vec<&str> = vec!["1-5",  "20-25", "10-15", "25-30"];
-// let test_expand_right_str_vec: Vec<&str> = vec!["1-4", "3-7", "2-8", "6-10"];
-// let test_expand_left_str_vec: Vec<&str> = vec!["10-15", "8-13", "10-17", "16-20"];
-// let test_superset_str_vec: Vec<&str> = vec!["5-6", "5-6", "4-7", "3-8", "2-9", "2-9"];
-// let test_subset_str_vec: Vec<&str> = vec!["2-9", "3-8", "4-7", "5-6"];
+    // These started life as some commented out println! checks at the
+    // bottom of this file. Each list gets inserted in order and should
+    // end up merged down to the ranges listed with it.
 
-// let tests:Vec<Vec<&str>> = vec![test_mutually_exclusice_str_vec, test_expand_right_str_vec, test_expand_left_str_vec, test_superset_str_vec, test_subset_str_vec];
+    fn build(ranges: &[&str]) -> IntervalSet<usize> {
+        let mut range_list = IntervalSet::new();
+        for (i, range) in ranges.iter().enumerate() {
+            let range = Range::parse(i + 1, range).unwrap();
+            range_list.insert(range.start..=range.end);
+        }
+        range_list
+    }
 
-// for test in tests {
-//     println!("Testing with list: {:?}", test);
-//     let mut range_list = RangeList{ranges: vec![]};
-//     for input in test {
-//         // println!("inserting: {}", input);
-//         range_list.insert(input);
-//         range_list.print();
+    fn bounds(range_list: &IntervalSet<usize>) -> Vec<(usize, usize)> {
+        range_list.iter().map(|r| (*r.start(), *r.end())).collect()
+    }
 
-//         println!("Is num 7 in range?: {}", range_list.find(7));
-//     }
-//     range_list.print();
+    #[test]
+    fn insert_mutually_exclusive() {
+        let range_list = build(&["1-5", "20-25", "10-15", "25-30"]);
+        assert_eq!(bounds(&range_list), vec![(1, 5), (10, 15), (20, 30)]);
+    }
+
+    #[test]
+    fn insert_expand_right() {
+        let range_list = build(&["1-4", "3-7", "2-8", "6-10"]);
+        assert_eq!(bounds(&range_list), vec![(1, 10)]);
+    }
+
+    #[test]
+    fn insert_expand_left() {
+        let range_list = build(&["10-15", "8-13", "10-17", "16-20"]);
+        assert_eq!(bounds(&range_list), vec![(8, 20)]);
+    }
+
+    #[test]
+    fn insert_superset() {
+        let range_list = build(&["5-6", "5-6", "4-7", "3-8", "2-9", "2-9"]);
+        assert_eq!(bounds(&range_list), vec![(2, 9)]);
+    }
+
+    #[test]
+    fn insert_subset() {
+        let range_list = build(&["2-9", "3-8", "4-7", "5-6"]);
+        assert_eq!(bounds(&range_list), vec![(2, 9)]);
+    }
 
-//     println!();
-// }
+    #[test]
+    fn insert_merges_adjacent_ranges() {
+        let range_list = build(&["2-4", "8-9", "5-6"]);
+        assert_eq!(bounds(&range_list), vec![(2, 6), (8, 9)]);
+    }
+
+    #[test]
+    fn insert_swallows_several_ranges() {
+        let range_list = build(&["1-3", "4-6", "8-9", "11-13", "5-12"]);
+        assert_eq!(bounds(&range_list), vec![(1, 13)]);
+    }
+
+    #[test]
+    fn find() {
+        let range_list = build(&["1-5", "10-15", "20-30"]);
+
+        for num in [1, 3, 5, 10, 15, 20, 25, 30] {
+            assert!(range_list.contains(num), "{} should be found", num);
+        }
+
+        for num in [0, 6, 9, 16, 19, 31, 100] {
+            assert!(!range_list.contains(num), "{} shouldn't be found", num);
+        }
+    }
+
+    #[test]
+    fn find_in_empty_list() {
+        let range_list = build(&[]);
+        assert!(!range_list.contains(7));
+    }
+
+    #[test]
+    fn parse_flips_backwards_ranges() {
+        let range = Range::parse(1, "9-3").unwrap();
+        assert_eq!((range.start, range.end), (3, 9));
+    }
+
+    #[test]
+    fn parse_rejects_bad_ranges() {
+        assert_eq!(Range::parse(4, "3").unwrap_err().line, 4);
+        assert_eq!(Range::parse(4, "3-x").unwrap_err().column, 3);
+    }
+}
@@ -1,17 +1,62 @@
 use regex::Regex;
 
+use crate::answer::Answer;
+use crate::error::ParseError;
+use crate::grid::Grid;
+use crate::solver::Solver;
+
 // 4 rows of digits then one row of symbols. I can probably just
 // split things up into some vecs then do a little matching and 
 // perform the right operations.
 
 
-pub fn solve(content: &str) {
+/// Read the worksheet into a grid of numbers and the row of operators
+/// at the bottom. Each column of the grid is one problem.
+pub fn parse_worksheet(content: &str) -> Result<(Grid<usize>, Vec<&str>), ParseError> {
+
+    let re = Regex::new(r"\S+").unwrap();
+
+    // split the input into vecs, hanging on to the line and column
+    // of each value so we can point at it if it turns out to be junk
+    let mut content_vecs: Vec<Vec<(usize, usize, &str)>> = content.lines()
+        .enumerate()
+        .map(|(i, line)| re.find_iter(line).map(|m| (i + 1, m.start() + 1, m.as_str())).collect::<Vec<_>>())
+        .collect();
+    let Some(operators) = content_vecs.pop() else {
+        return Err(ParseError::new(1, 1, content, "input needs rows of numbers and a row of operators"));
+    };
+
+    // parse all of the numbers up front
+    let mut number_rows: Vec<Vec<usize>> = vec![];
+    for row in content_vecs {
+        if row.len() != operators.len() {
+            let (line, _, _) = row.first().copied().unwrap_or((1, 1, ""));
+            return Err(ParseError::new(line, 1, "", &format!("expected {} numbers on this row", operators.len())));
+        }
+
+        let mut numbers = vec![];
+        for (line, column, val) in row {
+            let Ok(num) = val.parse::<usize>() else {
+                return Err(ParseError::new(line, column, val, "expected a whole number"));
+            };
+            numbers.push(num);
+        }
+        number_rows.push(numbers);
+    }
+
+    let mut parsed_operators = vec![];
+    for (line, column, operator) in operators {
+        if operator != "+" && operator != "*" {
+            return Err(ParseError::new(line, column, operator, "Not an operator I know what to do with"));
+        }
+        parsed_operators.push(operator);
+    }
 
-    let re = Regex::new(r"[ \s]+").unwrap();
+    Ok((Grid::from_rows(number_rows)?, parsed_operators))
+}
 
-    // split the input into vecs
-    let mut content_vecs: Vec<Vec<&str>> = content.lines().map(|line| re.split(line.trim()).collect::<Vec<&str>>()).collect();
-    let operators: Vec<&str> = content_vecs.pop().unwrap();
+pub fn solve(content: &str) -> Result<Answer, ParseError> {
+    let (number_rows, operators) = parse_worksheet(content)?;
     let mut solution = 0;
 
     for (idx, operator) in operators.iter().enumerate() {
@@ -19,17 +64,41 @@ pub fn solve(content: &str) {
         let init = match *operator {
             "+" => 0,
             "*" => 1,
-            _ => panic!("Not an operator I know what to do with")
+            _ => unreachable!("operators were checked when parsing")
         };
 
         let result = match *operator {
-            "+" => content_vecs.iter().fold(init, |acc, vec| acc + vec[idx].parse::<usize>().unwrap()),
-            "*" => content_vecs.iter().fold(init, |acc, vec| acc * vec[idx].parse::<usize>().unwrap()),
-            _ => panic!("Not an operator I know what to do with")
+            "+" => number_rows.column(idx).fold(init, |acc, num| acc + num),
+            "*" => number_rows.column(idx).fold(init, |acc, num| acc * num),
+            _ => unreachable!("operators were checked when parsing")
         };
 
         solution += result
     };
 
-    println!("solution: {}", solution);
-}
\ No newline at end of file
+    Ok(solution.into())
+}
+
+pub struct Solution;
+
+impl Solver for Solution {
+    fn day(&self) -> u8 {
+        6
+    }
+
+    fn part(&self) -> u8 {
+        1
+    }
+
+    fn name(&self) -> &'static str {
+        "grand total of the worksheet"
+    }
+
+    fn parse(&self, content: &str) -> Option<Result<(), ParseError>> {
+        Some(parse_worksheet(content).map(|_| ()))
+    }
+
+    fn solve(&self, content: &str) -> Result<Answer, ParseError> {
+        solve(content)
+    }
+}
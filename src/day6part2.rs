@@ -1,12 +1,16 @@
+use crate::answer::Answer;
+use crate::error::ParseError;
+use crate::grid::Grid;
+use crate::solver::Solver;
 
 // 4 rows of digits then one row of symbols. I can probably just
 // split things up into some vecs, mix things around, then perform
 //  the right operations... you know, draw the rest of the owl!
 
 
-/// Taking a matrix, return a matrix of new numbers
+/// Taking a grid of characters, return a matrix of new numbers
 /// New numbers consist of all of the same digits found in the input
-/// vec, but they have been rearranged. Imagine lining all of the numbers
+/// grid, but they have been rearranged. Imagine lining all of the numbers
 /// up vertically and left aligned such that each digit is in a column.
 /// Starting at the right most column, scan down the list top to bottom.
 /// Build a new number left to right using the digits found in that column.
@@ -15,46 +19,51 @@
 /// becomes
 /// [[3, 25, 1461],
 ///  [0, 69, 258, 1247]]
-pub fn decephalapodize(input: Vec<Vec<&str>>) -> Vec<Vec<usize>> {
-    let height = input.len();
-    let width = input[0].len();
-
+/// Returns a ParseError if a column has something other than digits and
+/// spaces in it. The grid already made sure the rows are all the same width.
+pub fn decephalapodize(input: &Grid<char>) -> Result<Vec<Vec<usize>>, ParseError> {
     let mut results: Vec<Vec<usize>> = vec![];
     let mut output_col = 0;
 
-    
-    for w in (0..width).rev() {
-        let mut new_num: Vec<&str> = vec![];
+    // the grid hands over the columns directly, so all that's left is to
+    // walk them from right to left
+    for (w, column) in input.columns().enumerate().rev() {
+        let new_num: Vec<char> = column.copied().collect();
 
         if results.len() <= output_col {
             results.push(vec![])
         }
 
-        for h in 0..height {
-            new_num.push(input[h][w]);
-        };
-
-        let digit = new_num.join("").trim().to_string();
+        let digit = new_num.iter().collect::<String>().trim().to_string();
 
         match digit.as_str() {
             "" => {
                 output_col += 1;
             }
             _ => {
-                results[output_col].push(digit.parse::<usize>().unwrap());
+                let Ok(num) = digit.parse::<usize>() else {
+                    // point at the first thing in this column that isn't a digit
+                    let (h, bad) = new_num.iter().enumerate()
+                        .find(|(_, c)| !(c.is_ascii_digit() || **c == ' '))
+                        .map(|(h, c)| (h, c.to_string()))
+                        .unwrap_or((0, digit.clone()));
+                    return Err(ParseError::new(h + 1, w + 1, &bad, "expected a digit or a space"));
+                };
+                results[output_col].push(num);
 
             }
         }
     }
 
-    results
+    Ok(results)
 }
 
+/// Read the worksheet, returning the cephalopod numbers (see decephalapodize)
+/// along with the operators from the bottom row.
+pub fn parse_worksheet(content: &str) -> Result<(Vec<Vec<usize>>, Vec<&str>), ParseError> {
 
-pub fn solve(content: &str) {
-
-    // split the input into vecs. The main difference from part 1 is that
-    // I'm splitting each row into all of its characters and not trimming or
+    // split the input into lines. The main difference from part 1 is that
+    // I'm keeping every character of each row and not trimming or
     // joining since the spaces are significant here. I did a classic not read
     // the problem fully at first and went down the wrong path for a minute by
     // wanting to transpose the table, when in reality, the numbers are already 
@@ -64,26 +73,31 @@ pub fn solve(content: &str) {
     //    45 64  387 23 
     //     6 98  215 314
     //   *   +   *   +  
-    // 
-    // gets transformed into
-    // ["1", "2", "3", " ", "3", "2", "8", " ", " ", "5", "1", " ", "6", "4", " "]
-    // [" ", "4", "5", " ", "6", "4", " ", " ", "3", "8", "7", " ", "2", "3", " "]
-    // [" ", " ", "6", " ", "9", "8", " ", " ", "2", "1", "5", " ", "3", "1", "4"]
-    // ["*", " ", " ", " ", "+", " ", " ", " ", "*", " ", " ", " ", "+", " ", " "]
-    let mut content_vecs: Vec<Vec<&str>> = content.lines().map(|line| line.split("").filter(|s| s != &"").collect::<Vec<&str>>()).collect();
-
-    // the final row in this parsed data is operators, so pop that out and 
-    // while we are at it, get rid of the spaces since we don't need them later
-    // ["*", " ", " ", " ", "+", " ", " ", " ", "*", " ", " ", " ", "+", " ", " "]
-    // becomes ["*", "+", "*", "+"]
-    let operators: Vec<&str> = content_vecs.pop().unwrap().into_iter().filter(|s| *s == "*" || *s == "+").collect();
-    
+    let mut lines: Vec<&str> = content.lines().collect();
+
+    // the final row is operators, so pop that out and while we are at it,
+    // get rid of the spaces since we don't need them later
+    // "*   +   *   +  " becomes ["*", "+", "*", "+"]
+    let Some(operator_row) = lines.pop() else {
+        return Err(ParseError::new(1, 1, content, "input needs rows of numbers and a row of operators"));
+    };
+    let operator_line = lines.len() + 1;
+    if let Some((column, bad)) = operator_row.chars().enumerate().find(|(_, c)| !['*', '+', ' '].contains(c)) {
+        return Err(ParseError::new(operator_line, column + 1, &bad.to_string(), "Not an operator I know what to do with"));
+    }
+    let operators: Vec<&str> = operator_row.matches(['*', '+']).collect();
+
+    // everything above that goes into a grid of characters
+    // ['1', '2', '3', ' ', '3', '2', '8', ' ', ' ', '5', '1', ' ', '6', '4', ' ']
+    // [' ', '4', '5', ' ', '6', '4', ' ', ' ', '3', '8', '7', ' ', '2', '3', ' ']
+    // [' ', ' ', '6', ' ', '9', '8', ' ', ' ', '2', '1', '5', ' ', '3', '1', '4']
+    let number_grid = Grid::parse(&lines.join("\n"), Ok)?;
+
     // Move all the numbers around so that we have all the numbers we need to crunch
     // together AND transpose into the same vec reading right to left
-    // ["1", "2", "3", " ", "3", "2", "8", " ", " ", "5", "1", " ", "6", "4", " "]
-    // [" ", "4", "5", " ", "6", "4", " ", " ", "3", "8", "7", " ", "2", "3", " "]
-    // [" ", " ", "6", " ", "9", "8", " ", " ", "2", "1", "5", " ", "3", "1", "4"]
-    // ["*", " ", " ", " ", "+", " ", " ", " ", "*", " ", " ", " ", "+", " ", " "]
+    // ['1', '2', '3', ' ', '3', '2', '8', ' ', ' ', '5', '1', ' ', '6', '4', ' ']
+    // [' ', '4', '5', ' ', '6', '4', ' ', ' ', '3', '8', '7', ' ', '2', '3', ' ']
+    // [' ', ' ', '6', ' ', '9', '8', ' ', ' ', '2', '1', '5', ' ', '3', '1', '4']
     //
     // becomes
     // [[4, 431, 623], 
@@ -91,7 +105,18 @@ pub fn solve(content: &str) {
     //  [8, 248, 369], 
     //  [356, 24, 1]]
     // AND they are actual numbers rather than strings
-    let cephalopod_numbers: Vec<Vec<usize>> = decephalapodize(content_vecs);    
+    let cephalopod_numbers: Vec<Vec<usize>> = decephalapodize(&number_grid)?;
+
+    // each group of numbers needs exactly one operator
+    if cephalopod_numbers.len() != operators.len() {
+        return Err(ParseError::new(operator_line, 1, &operators.concat(), &format!("expected {} operators", cephalopod_numbers.len())));
+    }
+
+    Ok((cephalopod_numbers, operators))
+}
+
+pub fn solve(content: &str) -> Result<Answer, ParseError> {
+    let (cephalopod_numbers, operators) = parse_worksheet(content)?;
 
     // Now that our data is in the right shape, we will iterate over
     // each operator we have and use them on the data. 
@@ -111,7 +136,7 @@ pub fn solve(content: &str) {
         let init = match *operator {
             "+" => 0,
             "*" => 1,
-            _ => panic!("Not an operator I know what to do with")
+            _ => unreachable!("operators were checked above")
         };
 
         // Compared to part 1, all of the numbers we want are already in the same
@@ -120,12 +145,78 @@ pub fn solve(content: &str) {
         let result = match *operator {
             "+" => cephalopod_numbers[idx].iter().fold(init, |acc, num| acc + num),
             "*" => cephalopod_numbers[idx].iter().fold(init, |acc, num| acc * num),
-            _ => panic!("Not an operator I know what to do with")
+            _ => unreachable!("operators were checked above")
         };
 
         solution += result
     };
 
 
-    println!("solution: {:?}", solution);
-}
\ No newline at end of file
+    Ok(solution.into())
+}
+
+pub struct Solution;
+
+impl Solver for Solution {
+    fn day(&self) -> u8 {
+        6
+    }
+
+    fn part(&self) -> u8 {
+        2
+    }
+
+    fn name(&self) -> &'static str {
+        "grand total of the cephalopod worksheet"
+    }
+
+    fn parse(&self, content: &str) -> Option<Result<(), ParseError>> {
+        Some(parse_worksheet(content).map(|_| ()))
+    }
+
+    fn solve(&self, content: &str) -> Result<Answer, ParseError> {
+        solve(content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn split_chars(rows: &[&str]) -> Grid<char> {
+        Grid::parse(&rows.join("\n"), Ok).unwrap()
+    }
+
+    #[test]
+    fn decephalapodize_example() {
+        let input = split_chars(&[
+            "123 328  51 64 ",
+            " 45 64  387 23 ",
+            "  6 98  215 314",
+        ]);
+
+        assert_eq!(
+            decephalapodize(&input).unwrap(),
+            vec![vec![4, 431, 623], vec![175, 581, 32], vec![8, 248, 369], vec![356, 24, 1]]
+        );
+    }
+
+    #[test]
+    fn decephalapodize_single_row() {
+        let input = split_chars(&["12 34"]);
+        assert_eq!(decephalapodize(&input).unwrap(), vec![vec![4, 3], vec![2, 1]]);
+    }
+
+    #[test]
+    fn parse_worksheet_rejects_ragged_rows() {
+        let e = parse_worksheet("12 34\n1 2\n+  * ").unwrap_err();
+        assert_eq!(e.line, 2);
+    }
+
+    #[test]
+    fn decephalapodize_points_at_bad_characters() {
+        let input = split_chars(&["12 34", "1x 56"]);
+        let e = decephalapodize(&input).unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (2, 2, "x"));
+    }
+}
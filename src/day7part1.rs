@@ -1,5 +1,10 @@
 use std::collections::HashSet;
 
+use crate::answer::Answer;
+use crate::error::ParseError;
+use crate::grid::Grid;
+use crate::solver::Solver;
+
 // First thoughts: 
 // How do I get away from the visual representation
 // and into something more concrete/condensed?
@@ -10,33 +15,34 @@ use std::collections::HashSet;
 // there is a laser at the same index as the splitter, remove 
 // the beam from the set and add beams at i+1 and i-1
 
-pub fn solve(content: &str) {
+/// Read the manifold, returning the index of the starting laser (S) and
+/// the indexes of the splitters (^) on each row that has any.
+pub fn parse_manifold(content: &str) -> Result<(usize, Vec<Vec<usize>>), ParseError> {
 
-    // Initialize our vec as well as a variable for marking
-    // the starting location
-    let mut beam_splitters: Vec<Vec<usize>> = vec![];
-    let mut starting_laser_index = 0;
+    // read the manifold into a grid, which also makes sure there's
+    // nothing in there we don't recognize
+    let manifold = Grid::parse(content, |c| match c {
+        '.' | 'S' | '^' => Ok(c),
+        _ => Err("manifold can only contain ., S, or ^"),
+    })?;
 
-    // extract the splitter indexes
-    // we will later iterate over these locations
-    for line in content.split("\n").collect::<Vec<&str>>() {
-        let mut splitters = vec![];
+    let Some(&(_, starting_laser_index)) = manifold.find_all(&'S').first() else {
+        return Err(ParseError::new(1, 1, "", "manifold needs a starting point S"));
+    };
 
-        for (idx, c) in line.chars().enumerate() {
-            if c == 'S' {
-                starting_laser_index = idx;
-            }
-
-            if c == '^' {
-                splitters.push(idx);
-            }
-        }
+    // extract the splitter indexes for each row that has any
+    // we will later iterate over these locations
+    let beam_splitters: Vec<Vec<usize>> = manifold
+        .rows()
+        .map(|row| row.iter().enumerate().filter(|(_, c)| **c == '^').map(|(idx, _)| idx).collect::<Vec<usize>>())
+        .filter(|splitters| !splitters.is_empty())
+        .collect();
 
-        if splitters.len() > 0 {
-            beam_splitters.push(splitters);
-        }
-    }
+    Ok((starting_laser_index, beam_splitters))
+}
 
+pub fn solve(content: &str) -> Result<Answer, ParseError> {
+    let (starting_laser_index, beam_splitters) = parse_manifold(content)?;
 
     // Set up a current set of beam locations and a 
     // next set of beam locations. When we iterate through
@@ -47,7 +53,7 @@ pub fn solve(content: &str) {
     // a set handles duplicates, and we need two sets to 
     // be able to process one row without messing it up by
     // making changes in place. 
-    let mut cur_beams: HashSet<usize> = HashSet::new();
+    let mut cur_beams: HashSet<usize>;
     let mut next_beams: HashSet<usize> = HashSet::new();
 
     // initialize next beams with the idx of the starting beam
@@ -73,5 +79,29 @@ pub fn solve(content: &str) {
         }
     }
 
-    println!("counter: {:?}", counter);
-}
\ No newline at end of file
+    Ok(counter.into())
+}
+
+pub struct Solution;
+
+impl Solver for Solution {
+    fn day(&self) -> u8 {
+        7
+    }
+
+    fn part(&self) -> u8 {
+        1
+    }
+
+    fn name(&self) -> &'static str {
+        "times the beam is split"
+    }
+
+    fn parse(&self, content: &str) -> Option<Result<(), ParseError>> {
+        Some(parse_manifold(content).map(|_| ()))
+    }
+
+    fn solve(&self, content: &str) -> Result<Answer, ParseError> {
+        solve(content)
+    }
+}
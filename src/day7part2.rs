@@ -1,6 +1,11 @@
 use std::collections::HashMap;
 use std::collections::HashSet;
 
+use crate::answer::Answer;
+use crate::day7part1::parse_manifold;
+use crate::error::ParseError;
+use crate::solver::Solver;
+
 // First thoughts: 
 // How do I get away from the visual representation
 // and into something more concrete/condensed?
@@ -34,7 +39,7 @@ pub fn recursive_traverse_graph(paths: HashMap<(usize, usize), HashSet<(usize, u
        
         // return the counts if we have already calcuated it
         if let Some(existing_counts) = path_counts.get(&cur) {
-            return *existing_counts
+            *existing_counts
         } 
         else {
             // otherwise, calculate the counts
@@ -43,41 +48,18 @@ pub fn recursive_traverse_graph(paths: HashMap<(usize, usize), HashSet<(usize, u
             }
             path_counts.insert(cur, child_path_counts);
 
-            return child_path_counts
+            child_path_counts
         }
     } else {
         // if there are no children, there is only one path
-        return 1
+        1
     }
 }
 
 // This solution is a little gross. It is all in one big method here.
-pub fn solve(content: &str) {
-
-    // Initialize our vec as well as a variable for marking
-    // the starting location
-    let mut beam_splitters: Vec<Vec<usize>> = vec![];
-    let mut starting_laser_index = 0;
-
-    // extract the splitter indexes
-    // we will later iterate over these locations
-    for line in content.split("\n").collect::<Vec<&str>>() {
-        let mut splitters = vec![];
-
-        for (idx, c) in line.chars().enumerate() {
-            if c == 'S' {
-                starting_laser_index = idx;
-            }
-
-            if c == '^' {
-                splitters.push(idx);
-            }
-        }
+pub fn solve(content: &str) -> Result<Answer, ParseError> {
 
-        if splitters.len() > 0 {
-            beam_splitters.push(splitters);
-        }
-    }
+    let (starting_laser_index, beam_splitters) = parse_manifold(content)?;
 
     // Same general idea as in part 1, but instead
     // of using a set to just track whether a beam exists
@@ -89,15 +71,14 @@ pub fn solve(content: &str) {
     // So instead of sets, I'm using maps.
 
     // Maps for ingesting the graph and making sure we split at the right place
-    let mut cur_beams: HashMap<usize, HashSet<(usize, usize)>> = HashMap::new();
+    let mut cur_beams: HashMap<usize, HashSet<(usize, usize)>>;
     let mut next_beams: HashMap<usize, HashSet<(usize, usize)>> = HashMap::new();
 
     // Map for building a representation of the graph
     let mut paths: HashMap<(usize, usize), HashSet<(usize, usize)>> = HashMap::new();
-    let mut path_joins = 0;
 
     // initialize next beams with the idx of the starting beam
-    next_beams.insert(starting_laser_index, HashSet::from([(starting_laser_index, 0 as usize)]));
+    next_beams.insert(starting_laser_index, HashSet::from([(starting_laser_index, 0)]));
 
 
     // Now using the indexes of the splitters in order
@@ -133,5 +114,29 @@ pub fn solve(content: &str) {
     let mut path_counts:  HashMap<(usize, usize), usize> = HashMap::new();
     let result = recursive_traverse_graph(paths, &mut path_counts, (starting_laser_index,0));
 
-    println!("result: {:?}", result);
-}
\ No newline at end of file
+    Ok(result.into())
+}
+
+pub struct Solution;
+
+impl Solver for Solution {
+    fn day(&self) -> u8 {
+        7
+    }
+
+    fn part(&self) -> u8 {
+        2
+    }
+
+    fn name(&self) -> &'static str {
+        "timelines through the manifold"
+    }
+
+    fn parse(&self, content: &str) -> Option<Result<(), ParseError>> {
+        Some(parse_manifold(content).map(|_| ()))
+    }
+
+    fn solve(&self, content: &str) -> Result<Answer, ParseError> {
+        solve(content)
+    }
+}
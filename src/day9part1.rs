@@ -1,5 +1,9 @@
 use std::fmt;
 
+use crate::answer::Answer;
+use crate::error::ParseError;
+use crate::solver::Solver;
+
 // A given point can be one corner of a square
 // and a connecting point must be its opposite.
 // So each point could possibly any corner, and
@@ -22,7 +26,7 @@ use std::fmt;
 // 
 // OOOORRrrrrr I could brute force it. Rust go brrrr!
 
-struct Point {
+pub struct Point {
     x: isize,
     y: isize
 }
@@ -34,6 +38,23 @@ impl fmt::Debug for Point {
 }
 
 impl Point {
+    /// Read a point from a line like "7,1"
+    pub fn parse(line_number: usize, line: &str) -> Result<Self, ParseError> {
+        let Some((x, y)) = line.split_once(",") else {
+            return Err(ParseError::new(line_number, 1, line, "a point should look like x,y"));
+        };
+
+        let Ok(x) = x.parse() else {
+            return Err(ParseError::new(line_number, 1, x, "x coordinate must be a whole number"));
+        };
+
+        let Ok(y) = y.parse() else {
+            return Err(ParseError::new(line_number, line.find(',').unwrap() + 2, y, "y coordinate must be a whole number"));
+        };
+
+        Ok(Point{x, y})
+    }
+
     pub fn area(&self, other: &Point) -> isize {
 
         let x_diff = (self.x - other.x + 1).abs();
@@ -43,13 +64,18 @@ impl Point {
     }
 }
 
-pub fn solve(content: &str) {
+/// Read every red tile, one per line
+pub fn parse_points(content: &str) -> Result<Vec<Point>, ParseError> {
+    let mut points: Vec<Point> = vec![];
+    for (line_index, line) in content.lines().enumerate() {
+        points.push(Point::parse(line_index + 1, line)?);
+    }
+    Ok(points)
+}
+
+pub fn solve(content: &str) -> Result<Answer, ParseError> {
 
-    let points: Vec<Point> = content.lines()
-        .into_iter()
-        .map(|line| line.split(","))
-        .map(|mut split| Point{x: split.next().unwrap().parse().unwrap(), y: split.next().unwrap().parse().unwrap()})
-        .collect();
+    let points = parse_points(content)?;
 
     // How about an ugly n^2 attempt?
     // for each point, check the distance of all other points
@@ -63,5 +89,29 @@ pub fn solve(content: &str) {
         }
     }
 
-    println!("{:?}", largest_area);
-}
\ No newline at end of file
+    Ok(largest_area.into())
+}
+
+pub struct Solution;
+
+impl Solver for Solution {
+    fn day(&self) -> u8 {
+        9
+    }
+
+    fn part(&self) -> u8 {
+        1
+    }
+
+    fn name(&self) -> &'static str {
+        "largest rectangle between red tiles"
+    }
+
+    fn parse(&self, content: &str) -> Option<Result<(), ParseError>> {
+        Some(parse_points(content).map(|_| ()))
+    }
+
+    fn solve(&self, content: &str) -> Result<Answer, ParseError> {
+        solve(content)
+    }
+}
@@ -0,0 +1,160 @@
+// The dial from day 1. Both parts had their own copy of a 0-99 dial that
+// started at 50 and wrapped around by adding or subtracting 100 in a while
+// loop, which is one trip around the loop per lap of the dial, so R1000000000
+// took ten million of them. This one works out where the dial ends up (and
+// how many times it went past something on the way) with rem_euclid and
+// division instead, so a turn takes the same time no matter how big it is.
+//
+// Clicks are positive to turn right (towards bigger numbers) and negative
+// to turn left. They're i128s so any u64 turn fits either way, and the
+// counts are u128s since a single u64 turn can go past something almost
+// that many times.
+
+/// A dial numbered 0 to modulus - 1 that wraps around
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Dial {
+    modulus: i64,
+    position: i64,
+}
+
+impl Dial {
+    /// A dial with numbers 0 to modulus - 1 pointing at start. Panics if
+    /// the modulus isn't positive. A start outside the dial wraps around
+    /// onto it.
+    pub fn new(modulus: i64, start: i64) -> Self {
+        assert!(modulus > 0, "a dial needs at least one number on it, not {}", modulus);
+        Dial { modulus, position: start.rem_euclid(modulus) }
+    }
+
+    pub fn modulus(&self) -> i64 {
+        self.modulus
+    }
+
+    pub fn position(&self) -> i64 {
+        self.position
+    }
+
+    /// Whether the dial is pointing at target right now
+    pub fn is_at(&self, target: i64) -> bool {
+        self.position == target.rem_euclid(self.modulus)
+    }
+
+    /// How many times the dial would point at target while turning by
+    /// clicks, counting where it stops but not where it starts. Doesn't
+    /// actually turn the dial.
+    pub fn passes(&self, clicks: i128, target: i64) -> u128 {
+        let modulus = i128::from(self.modulus);
+        let position = i128::from(self.position);
+        let target = i128::from(target);
+
+        // how far we need to turn (in whichever direction we're going) to
+        // get to the target the first time. Being on it already doesn't
+        // count, that's a whole lap away.
+        let distance = if clicks >= 0 { target - position } else { position - target };
+        let first = match distance.rem_euclid(modulus) {
+            0 => modulus,
+            d => d,
+        } as u128;
+
+        // then once more every lap after that
+        let clicks = clicks.unsigned_abs();
+        if clicks < first {
+            0
+        } else {
+            (clicks - first) / modulus as u128 + 1
+        }
+    }
+
+    /// Turn the dial by clicks
+    pub fn rotate(&mut self, clicks: i128) {
+        // wrap the clicks first so adding them on can't overflow
+        let modulus = i128::from(self.modulus);
+        self.position = (i128::from(self.position) + clicks.rem_euclid(modulus)).rem_euclid(modulus) as i64;
+    }
+
+    /// Turn the dial by clicks and return how many times it pointed at
+    /// target along the way (see passes)
+    pub fn rotate_past(&mut self, clicks: i128, target: i64) -> u128 {
+        let passes = self.passes(clicks, target);
+        self.rotate(clicks);
+        passes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::cases;
+
+    // one click at a time, the way it actually happens
+    fn click_by_click(dial: Dial, clicks: i128, target: i64) -> (i64, u128) {
+        let mut position = dial.position();
+        let mut passes = 0;
+        for _ in 0..clicks.abs() {
+            position = (position + clicks.signum() as i64).rem_euclid(dial.modulus());
+            if position == target {
+                passes += 1;
+            }
+        }
+        (position, passes)
+    }
+
+    #[test]
+    fn wraps_both_ways() {
+        let mut dial = Dial::new(100, 50);
+        dial.rotate(250);
+        assert_eq!(dial.position(), 0);
+        dial.rotate(-1);
+        assert_eq!(dial.position(), 99);
+        dial.rotate(-1_000_000_000);
+        assert_eq!(dial.position(), 99);
+        assert_eq!(Dial::new(100, -1).position(), 99);
+    }
+
+    #[test]
+    fn passes_the_edge_cases() {
+        // landing on the target counts
+        assert_eq!(Dial::new(100, 50).passes(-50, 0), 1);
+        assert_eq!(Dial::new(100, 50).passes(50, 0), 1);
+        // starting on it doesn't
+        assert_eq!(Dial::new(100, 0).passes(-5, 0), 0);
+        assert_eq!(Dial::new(100, 0).passes(5, 0), 0);
+        // but a whole lap back round to it does
+        assert_eq!(Dial::new(100, 0).passes(-100, 0), 1);
+        assert_eq!(Dial::new(100, 50).passes(1000, 0), 10);
+        assert_eq!(Dial::new(100, 50).passes(0, 0), 0);
+        assert_eq!(Dial::new(100, 50).passes(1_000_000_000, 0), 10_000_000);
+    }
+
+    #[test]
+    fn huge_turns() {
+        let max = i128::from(u64::MAX);
+        let mut dial = Dial::new(100, 50);
+        assert_eq!(dial.rotate_past(max, 0), (u64::MAX as u128 + 50) / 100);
+        assert_eq!(dial.position(), ((50 + max) % 100) as i64);
+        assert_eq!(dial.rotate_past(-max, 0), (u64::MAX as u128 - 65) / 100 + 1);
+        assert_eq!(dial.position(), 50);
+
+        // even past what any instruction can ask for
+        let mut dial = Dial::new(i64::MAX, i64::MAX - 1);
+        dial.rotate(i128::MAX);
+        dial.rotate(i128::MIN);
+        assert_eq!(dial.position(), ((i128::from(i64::MAX) - 2).rem_euclid(i128::from(i64::MAX))) as i64);
+        assert_eq!(Dial::new(1, 0).passes(i128::MIN, 0), i128::MIN.unsigned_abs());
+    }
+
+    #[test]
+    fn matches_turning_one_click_at_a_time() {
+        cases(0xda3e39cb94b95bdb, 2000, |rng| {
+            let modulus = rng.below(20) as i64 + 1;
+            let mut dial = Dial::new(modulus, rng.below(40) as i64 - 20);
+            let clicks = rng.below(200) as i128 - 100;
+            let target = rng.below(modulus as u64) as i64;
+
+            let (position, passes) = click_by_click(dial, clicks, target);
+            assert_eq!(dial.rotate_past(clicks, target), passes, "{:?} turning {} past {}", dial, clicks, target);
+            assert_eq!(dial.position(), position);
+            assert_eq!(dial.is_at(target), position == target);
+        });
+    }
+}
@@ -0,0 +1,49 @@
+use std::error::Error;
+use std::fmt;
+
+// Most of the days used to panic! or unwrap() as soon as something in the
+// input looked off, which is fine when the input is perfect but pretty
+// unhelpful when a file gets truncated or picks up a stray character.
+// Instead, parsers hand back a ParseError that says exactly where the
+// problem is so it can be fixed without digging through a backtrace.
+
+/// Something in the puzzle input that we couldn't make sense of. Lines
+/// and columns both start at 1, like in a text editor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+    pub message: String,
+}
+
+impl ParseError {
+    pub fn new(line: usize, column: usize, text: &str, message: &str) -> Self {
+        ParseError {
+            line,
+            column,
+            text: text.to_string(),
+            message: message.to_string(),
+        }
+    }
+
+    /// Build an error from a byte offset into the whole input, for the
+    /// days that don't parse line by line (like day 2's comma separated
+    /// ranges). Works out the line and column from the offset.
+    pub fn at_offset(content: &str, offset: usize, text: &str, message: &str) -> Self {
+        let before = &content[..offset];
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map(|idx| idx + 1).unwrap_or(0);
+        let column = before[line_start..].chars().count() + 1;
+
+        ParseError::new(line, column, text, message)
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}, column {}: {} (found {:?})", self.line, self.column, self.message, self.text)
+    }
+}
+
+impl Error for ParseError {}
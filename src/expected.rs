@@ -0,0 +1,154 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::answer::Answer;
+use crate::error::ParseError;
+use crate::runner::{self, InputSource};
+use crate::solver::Registry;
+
+// There was nothing stopping a refactor from quietly changing an answer,
+// so src/inputs/answers.toml keeps a record of the answers we know are
+// right and `verify` checks every solver against them.
+//
+// The file is a tiny bit of TOML: an [input] section for dayN.txt and a
+// [test] section for dayN-test.txt, each with lines like `4-1 = 1351` or
+// `1-1 = "1123 (final dial position 49)"`. It's a small enough subset
+// that I'd rather read it by hand than pull in a TOML crate.
+
+/// Where the answers manifest lives
+pub fn answers_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("src").join("inputs").join("answers.toml")
+}
+
+/// The known answers for the real inputs and the example inputs, keyed by
+/// day-part. Answers are kept as the text the solver's answer displays as.
+pub struct ExpectedAnswers {
+    pub input: HashMap<String, String>,
+    pub test: HashMap<String, String>,
+}
+
+impl ExpectedAnswers {
+    /// Read the manifest from src/inputs/answers.toml
+    pub fn load() -> Result<Self, String> {
+        let path = answers_path();
+        let content = fs::read_to_string(&path).map_err(|e| format!("couldn't read {}: {}", path.display(), e))?;
+        ExpectedAnswers::parse(&content).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    pub fn parse(content: &str) -> Result<Self, ParseError> {
+        let mut expected = ExpectedAnswers { input: HashMap::new(), test: HashMap::new() };
+        let mut section: Option<&str> = None;
+
+        for (line_index, line) in content.lines().enumerate() {
+            let line_number = line_index + 1;
+            let line = line.trim();
+
+            // skip blank lines and comments
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if line.starts_with('[') {
+                match line {
+                    "[input]" => section = Some("input"),
+                    "[test]" => section = Some("test"),
+                    _ => return Err(ParseError::new(line_number, 1, line, "section must be [input] or [test]")),
+                }
+                continue;
+            }
+
+            let Some((key, value)) = line.split_once('=') else {
+                return Err(ParseError::new(line_number, 1, line, "expected a line like 4-1 = 1351"));
+            };
+            let key = key.trim().trim_matches('"');
+            let value = value.trim();
+
+            // strings are quoted, numbers aren't
+            let value = if let Some(quoted) = value.strip_prefix('"') {
+                let Some(unquoted) = quoted.strip_suffix('"') else {
+                    return Err(ParseError::new(line_number, line.find('"').unwrap() + 1, value, "string answer is missing its closing quote"));
+                };
+                unquoted
+            } else if value.parse::<i128>().is_ok() {
+                value
+            } else {
+                return Err(ParseError::new(line_number, line.find('=').unwrap() + 2, value, "answer must be a number or a quoted string"));
+            };
+
+            let answers = match section {
+                Some("input") => &mut expected.input,
+                Some("test") => &mut expected.test,
+                _ => return Err(ParseError::new(line_number, 1, line, "answers need to go under [input] or [test]")),
+            };
+
+            if answers.insert(key.to_string(), value.to_string()).is_some() {
+                return Err(ParseError::new(line_number, 1, key, "this day-part already has an answer in this section"));
+            }
+        }
+
+        Ok(expected)
+    }
+}
+
+/// How a solver's answer compared to the one we have on record
+pub enum Verdict {
+    Pass,
+    Fail { expected: String, actual: Answer },
+    Missing { actual: Answer },
+    Error(String),
+}
+
+/// The verdict for one solver on one of its inputs
+pub struct Verification {
+    pub key: String,
+    pub test: bool,
+    pub verdict: Verdict,
+}
+
+/// Run every registered solver on both its real input and its example
+/// input and compare each answer against the manifest.
+pub fn verify(registry: &Registry, expected: &ExpectedAnswers) -> Vec<Verification> {
+    let mut results = vec![];
+
+    for solver in registry.iter() {
+        for test in [false, true] {
+            let answers = if test { &expected.test } else { &expected.input };
+            let result = runner::run(solver, &InputSource::Default { test });
+
+            let verdict = match (result.outcome, answers.get(&result.key)) {
+                (Err(e), _) => Verdict::Error(e),
+                (Ok(actual), None) => Verdict::Missing { actual },
+                (Ok(actual), Some(expected)) if actual.to_string() == *expected => Verdict::Pass,
+                (Ok(actual), Some(expected)) => Verdict::Fail { expected: expected.clone(), actual },
+            };
+
+            results.push(Verification { key: result.key, test, verdict });
+        }
+    }
+
+    results
+}
+
+/// Print how everything went, one line per solver and input
+pub fn print_verifications(results: &[Verification]) {
+    for result in results {
+        let input = if result.test { "test" } else { "input" };
+        let status = match &result.verdict {
+            Verdict::Pass => "pass".to_string(),
+            Verdict::Fail { expected, actual } => format!("FAIL expected {} but got {}", expected, actual),
+            Verdict::Missing { actual } => format!("missing (got {})", actual),
+            Verdict::Error(e) => format!("ERROR {}", e),
+        };
+        println!("{:<12} {:<6} {}", result.key, input, status);
+    }
+
+    let count = |f: fn(&Verdict) -> bool| results.iter().filter(|r| f(&r.verdict)).count();
+    println!(
+        "{} passed, {} failed, {} missing, {} errors",
+        count(|v| matches!(v, Verdict::Pass)),
+        count(|v| matches!(v, Verdict::Fail { .. })),
+        count(|v| matches!(v, Verdict::Missing { .. })),
+        count(|v| matches!(v, Verdict::Error(_))),
+    );
+}
@@ -0,0 +1,331 @@
+use std::fmt;
+
+use crate::error::ParseError;
+
+// Days 4, 6, and 7 all read a block of characters into some kind of 2d
+// structure and then poke around in it. Day 4 had two copies of the same
+// Matrix with an eight branch neighbors function, and day 7 kept scanning
+// the raw lines looking for S and ^. This is the shared version of all
+// of that.
+//
+// Everything is addressed as (row, col) with (0, 0) in the top left, the
+// same way the day 4 Matrix did it. The cells are stored in one flat Vec,
+// row after row, since that's a bit kinder than a Vec of Vecs.
+
+/// Offsets for the 4 neighbors that share an edge: N, E, S, W
+pub const FOUR_NEIGHBORS: [(isize, isize); 4] = [(-1, 0), (0, 1), (1, 0), (0, -1)];
+
+/// Offsets for all 8 surrounding neighbors: N, NE, E, SE, S, SW, W, NW
+pub const EIGHT_NEIGHBORS: [(isize, isize); 8] = [(-1, 0), (-1, 1), (0, 1), (1, 1), (1, 0), (1, -1), (0, -1), (-1, -1)];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    data: Vec<T>,
+    width: usize,
+    height: usize,
+}
+
+impl<T> Grid<T> {
+    /// Read a grid from the input, one row per line, using the closure to
+    /// turn each character into a cell. If the closure doesn't like a
+    /// character it returns an error message, which gets turned into a
+    /// ParseError pointing at that character. Every row has to be the same
+    /// width as the first.
+    pub fn parse<F>(content: &str, mut cell: F) -> Result<Self, ParseError>
+    where
+        F: FnMut(char) -> Result<T, &'static str>,
+    {
+        let mut data = vec![];
+        let mut width = 0;
+        let mut height = 0;
+
+        for (row, line) in content.lines().enumerate() {
+            let mut row_width = 0;
+
+            for (col, c) in line.chars().enumerate() {
+                match cell(c) {
+                    Ok(val) => data.push(val),
+                    Err(message) => return Err(ParseError::new(row + 1, col + 1, &c.to_string(), message)),
+                }
+                row_width += 1;
+            }
+
+            if row == 0 {
+                width = row_width;
+            } else if row_width != width {
+                return Err(ParseError::new(row + 1, 1, line, &format!("every row must be {} wide like the first one", width)));
+            }
+
+            height += 1;
+        }
+
+        Ok(Grid { data, width, height })
+    }
+
+    /// Build a grid out of rows that have already been read. The error
+    /// points at the first row that isn't as wide as the first one.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Self, ParseError> {
+        let width = rows.first().map(|row| row.len()).unwrap_or(0);
+        let height = rows.len();
+        let mut data = Vec::with_capacity(width * height);
+
+        for (row_index, row) in rows.into_iter().enumerate() {
+            if row.len() != width {
+                return Err(ParseError::new(row_index + 1, 1, "", &format!("every row must be {} wide like the first one", width)));
+            }
+            data.extend(row);
+        }
+
+        Ok(Grid { data, width, height })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    fn index(&self, row: usize, col: usize) -> Option<usize> {
+        if row < self.height && col < self.width {
+            Some(row * self.width + col)
+        } else {
+            None
+        }
+    }
+
+    /// The cell at (row, col), or None if that's off the grid
+    pub fn get(&self, row: usize, col: usize) -> Option<&T> {
+        self.index(row, col).map(|idx| &self.data[idx])
+    }
+
+    pub fn get_mut(&mut self, row: usize, col: usize) -> Option<&mut T> {
+        self.index(row, col).map(|idx| &mut self.data[idx])
+    }
+
+    /// Replace the cell at (row, col), handing back what used to be there.
+    /// Returns None (and changes nothing) if (row, col) is off the grid.
+    pub fn set(&mut self, row: usize, col: usize, val: T) -> Option<T> {
+        self.get_mut(row, col).map(|cell| std::mem::replace(cell, val))
+    }
+
+    /// Move from (row, col) by an offset, or None if that leaves the grid.
+    /// checked_add_signed does the work of making sure we don't go below 0.
+    pub fn offset(&self, row: usize, col: usize, (d_row, d_col): (isize, isize)) -> Option<(usize, usize)> {
+        let new_row = row.checked_add_signed(d_row)?;
+        let new_col = col.checked_add_signed(d_col)?;
+        self.index(new_row, new_col).map(|_| (new_row, new_col))
+    }
+
+    /// Move from (row, col) by an offset, wrapping around the edges like
+    /// the grid is drawn on a donut. Only None if the grid is empty.
+    pub fn wrapping_offset(&self, row: usize, col: usize, (d_row, d_col): (isize, isize)) -> Option<(usize, usize)> {
+        if self.is_empty() {
+            return None;
+        }
+        let new_row = (row as isize % self.height as isize + d_row % self.height as isize).rem_euclid(self.height as isize);
+        let new_col = (col as isize % self.width as isize + d_col % self.width as isize).rem_euclid(self.width as isize);
+        Some((new_row as usize, new_col as usize))
+    }
+
+    /// The coordinates of every neighbor of (row, col) at the given offsets
+    /// that is actually on the grid.
+    pub fn neighbors_at<'a>(&'a self, row: usize, col: usize, offsets: &'a [(isize, isize)]) -> impl Iterator<Item = (usize, usize)> + 'a {
+        offsets.iter().filter_map(move |&offset| self.offset(row, col, offset))
+    }
+
+    /// The coordinates of the neighbor of (row, col) at every one of the
+    /// offsets, wrapping around the edges. On a grid smaller than the
+    /// offsets reach, two offsets can land on the same cell (or back on
+    /// (row, col) itself) and it shows up once for each.
+    pub fn wrapping_neighbors_at<'a>(&'a self, row: usize, col: usize, offsets: &'a [(isize, isize)]) -> impl Iterator<Item = (usize, usize)> + 'a {
+        offsets.iter().filter_map(move |&offset| self.wrapping_offset(row, col, offset))
+    }
+
+    /// The up to 4 neighbors that share an edge with (row, col)
+    pub fn neighbors4(&self, row: usize, col: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.neighbors_at(row, col, &FOUR_NEIGHBORS)
+    }
+
+    /// The up to 8 neighbors surrounding (row, col), including diagonals
+    pub fn neighbors8(&self, row: usize, col: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.neighbors_at(row, col, &EIGHT_NEIGHBORS)
+    }
+
+    /// The cells of one row, left to right
+    pub fn row(&self, row: usize) -> Option<&[T]> {
+        if row < self.height {
+            Some(&self.data[row * self.width..(row + 1) * self.width])
+        } else {
+            None
+        }
+    }
+
+    /// Every row, top to bottom
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.height).map(move |row| &self.data[row * self.width..(row + 1) * self.width])
+    }
+
+    /// The cells of one column, top to bottom. Empty if the column is off
+    /// the grid.
+    pub fn column(&self, col: usize) -> impl Iterator<Item = &T> {
+        let rows = if col < self.width { self.height } else { 0 };
+        (0..rows).map(move |row| &self.data[row * self.width + col])
+    }
+
+    /// Every column, left to right
+    pub fn columns(&self) -> impl DoubleEndedIterator<Item = impl Iterator<Item = &T>> + ExactSizeIterator {
+        (0..self.width).map(move |col| self.column(col))
+    }
+
+    /// Every cell along with its (row, col)
+    pub fn cells(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        self.data.iter().enumerate().map(move |(idx, val)| ((idx / self.width, idx % self.width), val))
+    }
+
+    /// The (row, col) of every cell equal to val e.g. every 'S' in a char grid
+    pub fn find_all(&self, val: &T) -> Vec<(usize, usize)>
+    where
+        T: PartialEq,
+    {
+        self.cells().filter(|(_, cell)| *cell == val).map(|(pos, _)| pos).collect()
+    }
+
+    /// A new grid with the same shape with every cell run through f
+    pub fn map<U, F: FnMut(&T) -> U>(&self, f: F) -> Grid<U> {
+        Grid { data: self.data.iter().map(f).collect(), width: self.width, height: self.height }
+    }
+
+    /// Flip the grid over its diagonal so rows become columns
+    pub fn transpose(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        let data = self.columns().flat_map(|column| column.cloned()).collect();
+        Grid { data, width: self.height, height: self.width }
+    }
+}
+
+/// Prints each row on its own line with the cells right next to each other,
+/// which for a char grid gives back what the input looked like.
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (idx, row) in self.rows().enumerate() {
+            if idx > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chars(content: &str) -> Grid<char> {
+        Grid::parse(content, Ok).unwrap()
+    }
+
+    fn sorted(neighbors: impl Iterator<Item = (usize, usize)>) -> Vec<(usize, usize)> {
+        let mut neighbors: Vec<_> = neighbors.collect();
+        neighbors.sort();
+        neighbors
+    }
+
+    #[test]
+    fn parse_and_get() {
+        let grid = chars("abc\ndef");
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid.get(0, 0), Some(&'a'));
+        assert_eq!(grid.get(1, 2), Some(&'f'));
+        assert_eq!(grid.get(2, 0), None);
+        assert_eq!(grid.get(0, 3), None);
+    }
+
+    #[test]
+    fn parse_points_at_bad_characters() {
+        let e = Grid::parse("..\n.x", |c| if c == '.' { Ok(0) } else { Err("only dots") }).unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (2, 2, "x"));
+    }
+
+    #[test]
+    fn parse_rejects_ragged_rows() {
+        let e = Grid::parse("...\n..", Ok).unwrap_err();
+        assert_eq!(e.line, 2);
+    }
+
+    #[test]
+    fn parse_empty() {
+        let grid = chars("");
+        assert!(grid.is_empty());
+        assert_eq!(grid.rows().count(), 0);
+    }
+
+    #[test]
+    fn set() {
+        let mut grid = chars("ab\ncd");
+        assert_eq!(grid.set(1, 0, 'z'), Some('c'));
+        assert_eq!(grid.get(1, 0), Some(&'z'));
+        assert_eq!(grid.set(5, 0, 'z'), None);
+    }
+
+    #[test]
+    fn neighbors() {
+        let grid = chars("...\n...\n...");
+        assert_eq!(sorted(grid.neighbors4(1, 1)), vec![(0, 1), (1, 0), (1, 2), (2, 1)]);
+        assert_eq!(sorted(grid.neighbors8(1, 1)).len(), 8);
+        assert_eq!(sorted(grid.neighbors4(0, 0)), vec![(0, 1), (1, 0)]);
+        assert_eq!(sorted(grid.neighbors8(0, 0)), vec![(0, 1), (1, 0), (1, 1)]);
+        assert_eq!(sorted(grid.neighbors8(2, 1)), vec![(1, 0), (1, 1), (1, 2), (2, 0), (2, 2)]);
+    }
+
+    #[test]
+    fn wrapping_neighbors() {
+        let grid = chars("...\n...");
+        assert_eq!(grid.wrapping_offset(0, 0, (-1, -1)), Some((1, 2)));
+        assert_eq!(grid.wrapping_offset(1, 2, (1, 1)), Some((0, 0)));
+        assert_eq!(grid.wrapping_offset(0, 0, (-7, 10)), Some((1, 1)));
+        assert_eq!(sorted(grid.wrapping_neighbors_at(0, 0, &FOUR_NEIGHBORS)), vec![(0, 1), (0, 2), (1, 0), (1, 0)]);
+        assert_eq!(grid.wrapping_neighbors_at(0, 0, &EIGHT_NEIGHBORS).count(), 8);
+        assert_eq!(chars("").wrapping_offset(0, 0, (0, 1)), None);
+    }
+
+    #[test]
+    fn rows_and_columns() {
+        let grid = chars("abc\ndef");
+        assert_eq!(grid.rows().collect::<Vec<_>>(), vec![&['a', 'b', 'c'][..], &['d', 'e', 'f'][..]]);
+        assert_eq!(grid.column(1).collect::<String>(), "be");
+        assert_eq!(grid.column(3).count(), 0);
+        assert_eq!(grid.columns().rev().map(|c| c.collect::<String>()).collect::<Vec<_>>(), vec!["cf", "be", "ad"]);
+    }
+
+    #[test]
+    fn find_all() {
+        let grid = chars(".S.\n^.^");
+        assert_eq!(grid.find_all(&'S'), vec![(0, 1)]);
+        assert_eq!(grid.find_all(&'^'), vec![(1, 0), (1, 2)]);
+        assert!(grid.find_all(&'x').is_empty());
+    }
+
+    #[test]
+    fn transpose_and_display() {
+        let grid = chars("abc\ndef");
+        assert_eq!(grid.transpose().to_string(), "ad\nbe\ncf");
+        assert_eq!(grid.transpose().transpose(), grid);
+    }
+
+    #[test]
+    fn from_rows() {
+        assert_eq!(Grid::from_rows(vec![vec![1, 2], vec![3, 4]]).unwrap().to_string(), "12\n34");
+        assert_eq!(Grid::from_rows(vec![vec![1, 2], vec![3]]).unwrap_err().line, 2);
+    }
+}
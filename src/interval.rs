@@ -0,0 +1,422 @@
+use std::fmt;
+use std::ops::RangeInclusive;
+
+// Day 5 had the same RangeList copy and pasted into both parts, and it
+// only ever worked on usize, could only insert, and looked things up with
+// a hand-rolled binary search. This is the grown up version of it that
+// anything can use.
+//
+// An IntervalSet is a set of values stored as a sorted list of inclusive
+// ranges. The ranges never overlap and never touch (2-4 and 5-6 become
+// 2-6), so every value in the set is in exactly one range and there is
+// exactly one way to store any given set. Keeping it that way on every
+// insert and remove is what makes everything else simple.
+
+/// The bits of std::iter::Step we need, which is still nightly only.
+/// A type can be used in an IntervalSet if we can step to the next and
+/// previous values and count how far apart two values are.
+pub trait Step: Copy + Ord {
+    /// The next value up, or None if this is the biggest one
+    fn successor(self) -> Option<Self>;
+
+    /// The next value down, or None if this is the smallest one
+    fn predecessor(self) -> Option<Self>;
+
+    /// How many steps it takes to get from start up to end (end >= start)
+    fn steps_between(start: Self, end: Self) -> u128;
+}
+
+// Everything up to 64 bits fits in an i128, so doing the subtraction there
+// works for signed and unsigned types alike. The 128 bit types are left out
+// since a full range of those wouldn't fit in the u128 length.
+macro_rules! step_for_int {
+    ($($t:ty),*) => {
+        $(
+            impl Step for $t {
+                fn successor(self) -> Option<Self> {
+                    self.checked_add(1)
+                }
+
+                fn predecessor(self) -> Option<Self> {
+                    self.checked_sub(1)
+                }
+
+                fn steps_between(start: Self, end: Self) -> u128 {
+                    (end as i128 - start as i128) as u128
+                }
+            }
+        )*
+    };
+}
+
+step_for_int!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+/// True if a range ending at `end` and a range starting at `start` would
+/// overlap or sit right next to each other, meaning they should be merged.
+/// `end` is assumed to be the one further to the left.
+pub fn touches<T: Step>(end: T, start: T) -> bool {
+    match end.successor() {
+        Some(next) => next >= start,
+        // nothing comes after end, so everything to its right is covered
+        None => true,
+    }
+}
+
+#[derive(Clone, PartialEq, Eq)]
+pub struct IntervalSet<T> {
+    // sorted by start, never overlapping or touching
+    ranges: Vec<(T, T)>,
+}
+
+impl<T: Step> IntervalSet<T> {
+    pub fn new() -> Self {
+        IntervalSet { ranges: vec![] }
+    }
+
+    /// How many merged ranges there are (not how many values, see len)
+    pub fn range_count(&self) -> usize {
+        self.ranges.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// How many values are in the set. This is a u128 since an IntervalSet
+    /// of u64s covering everything has one more value than u64 can hold.
+    pub fn len(&self) -> u128 {
+        self.ranges.iter().map(|&(start, end)| T::steps_between(start, end) + 1).sum()
+    }
+
+    /// Add every value in the range to the set, merging it with any ranges
+    /// it overlaps or touches. Backwards ranges (like 5..=3) are empty and
+    /// don't add anything.
+    pub fn insert(&mut self, range: RangeInclusive<T>) {
+        let (start, end) = range.into_inner();
+        if start > end {
+            return;
+        }
+
+        // Everything in lo..hi overlaps or touches the new range. Ranges
+        // before lo end too far left to reach it, and ranges from hi on
+        // start too far right. Since the ranges are sorted and disjoint,
+        // both of those are a partition_point (aka a binary search).
+        let lo = self.ranges.partition_point(|&(_, r_end)| !touches(r_end, start));
+        let hi = self.ranges.partition_point(|&(r_start, _)| touches(end, r_start));
+
+        let merged_start = if lo < hi { start.min(self.ranges[lo].0) } else { start };
+        let merged_end = if lo < hi { end.max(self.ranges[hi - 1].1) } else { end };
+
+        self.ranges.splice(lo..hi, [(merged_start, merged_end)]);
+    }
+
+    /// Take every value in the range out of the set. Ranges that stick out
+    /// past either side of it get trimmed rather than dropped.
+    pub fn remove(&mut self, range: RangeInclusive<T>) {
+        let (start, end) = range.into_inner();
+        if start > end {
+            return;
+        }
+
+        // only lo..hi actually overlap the range being removed
+        let lo = self.ranges.partition_point(|&(_, r_end)| r_end < start);
+        let hi = self.ranges.partition_point(|&(r_start, _)| r_start <= end);
+        if lo == hi {
+            return;
+        }
+
+        let mut leftovers = vec![];
+
+        // the first one might start before the removed range
+        let (first_start, _) = self.ranges[lo];
+        if first_start < start {
+            leftovers.push((first_start, start.predecessor().unwrap()));
+        }
+
+        // and the last one might keep going after it
+        let (_, last_end) = self.ranges[hi - 1];
+        if last_end > end {
+            leftovers.push((end.successor().unwrap(), last_end));
+        }
+
+        self.ranges.splice(lo..hi, leftovers);
+    }
+
+    /// Whether the value is in any of the ranges
+    pub fn contains(&self, val: T) -> bool {
+        let idx = self.ranges.partition_point(|&(_, end)| end < val);
+        idx < self.ranges.len() && self.ranges[idx].0 <= val
+    }
+
+    /// The merged ranges, smallest first
+    pub fn iter(&self) -> impl Iterator<Item = RangeInclusive<T>> + '_ {
+        self.ranges.iter().map(|&(start, end)| start..=end)
+    }
+
+    /// The ranges of values that are missing between the first and last
+    /// values in the set, smallest first
+    pub fn gaps(&self) -> impl Iterator<Item = RangeInclusive<T>> + '_ {
+        // the ranges never touch, so there's always at least one value
+        // between the end of one and the start of the next
+        self.ranges.windows(2).map(|pair| pair[0].1.successor().unwrap()..=pair[1].0.predecessor().unwrap())
+    }
+
+    /// Add a range to the end while building a new set in sorted order,
+    /// merging with the last range if they touch.
+    fn push_sorted(&mut self, start: T, end: T) {
+        if let Some(last) = self.ranges.last_mut() && touches(last.1, start) {
+            last.1 = last.1.max(end);
+            return;
+        }
+        self.ranges.push((start, end));
+    }
+
+    /// Every value that's in either set
+    pub fn union(&self, other: &Self) -> Self {
+        // both lists are already sorted, so walk them together like the
+        // merge step of a merge sort
+        let mut result = IntervalSet::new();
+        let (mut i, mut j) = (0, 0);
+
+        while i < self.ranges.len() || j < other.ranges.len() {
+            let take_self = j == other.ranges.len() || (i < self.ranges.len() && self.ranges[i].0 <= other.ranges[j].0);
+            let (start, end) = if take_self {
+                i += 1;
+                self.ranges[i - 1]
+            } else {
+                j += 1;
+                other.ranges[j - 1]
+            };
+            result.push_sorted(start, end);
+        }
+
+        result
+    }
+
+    /// Every value that's in both sets
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut result = IntervalSet::new();
+        let (mut i, mut j) = (0, 0);
+
+        while i < self.ranges.len() && j < other.ranges.len() {
+            let (a_start, a_end) = self.ranges[i];
+            let (b_start, b_end) = other.ranges[j];
+
+            let start = a_start.max(b_start);
+            let end = a_end.min(b_end);
+            if start <= end {
+                result.push_sorted(start, end);
+            }
+
+            // whichever range ends first can't overlap anything else
+            if a_end < b_end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+
+        result
+    }
+
+    /// Every value that's in this set but not the other one
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut result = self.clone();
+        for range in other.iter() {
+            result.remove(range);
+        }
+        result
+    }
+}
+
+impl<T: Step> Default for IntervalSet<T> {
+    fn default() -> Self {
+        IntervalSet::new()
+    }
+}
+
+impl<T: Step> FromIterator<RangeInclusive<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = RangeInclusive<T>>>(iter: I) -> Self {
+        let mut set = IntervalSet::new();
+        for range in iter {
+            set.insert(range);
+        }
+        set
+    }
+}
+
+// Prints like [1-5, 10-15] which is how the day 5 input writes ranges
+impl<T: Step + fmt::Display> fmt::Debug for IntervalSet<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let ranges: Vec<String> = self.ranges.iter().map(|(start, end)| format!("{}-{}", start, end)).collect();
+        write!(f, "[{}]", ranges.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::*;
+    use crate::rng::{cases, Rng};
+
+    fn bounds<T: Step>(set: &IntervalSet<T>) -> Vec<(T, T)> {
+        set.iter().map(|r| (*r.start(), *r.end())).collect()
+    }
+
+    #[test]
+    fn insert_merges_overlapping_and_adjacent() {
+        let set: IntervalSet<u32> = [1..=3, 8..=9, 4..=5, 20..=25, 7..=7].into_iter().collect();
+        assert_eq!(bounds(&set), vec![(1, 5), (7, 9), (20, 25)]);
+
+        let set: IntervalSet<u32> = [1..=3, 5..=6, 8..=9, 11..=13, 4..=12].into_iter().collect();
+        assert_eq!(bounds(&set), vec![(1, 13)]);
+    }
+
+    #[test]
+    fn insert_ignores_empty_ranges() {
+        let mut set = IntervalSet::new();
+        #[allow(clippy::reversed_empty_ranges)]
+        set.insert(5..=3);
+        assert!(set.is_empty());
+    }
+
+    #[test]
+    fn insert_at_the_edges_of_the_type() {
+        let set: IntervalSet<u8> = [250..=255, 0..=3, 4..=10].into_iter().collect();
+        assert_eq!(bounds(&set), vec![(0, 10), (250, 255)]);
+        assert_eq!(set.len(), 17);
+
+        let everything: IntervalSet<u64> = [0..=u64::MAX].into_iter().collect();
+        assert_eq!(everything.len(), u64::MAX as u128 + 1);
+    }
+
+    #[test]
+    fn remove_trims_and_splits() {
+        let mut set: IntervalSet<i32> = [-10..=10, 20..=30].into_iter().collect();
+        set.remove(0..=0);
+        set.remove(25..=40);
+        assert_eq!(bounds(&set), vec![(-10, -1), (1, 10), (20, 24)]);
+
+        set.remove(-20..=22);
+        assert_eq!(bounds(&set), vec![(23, 24)]);
+    }
+
+    #[test]
+    fn contains() {
+        let set: IntervalSet<usize> = [1..=5, 10..=15, 20..=30].into_iter().collect();
+        for num in [1, 3, 5, 10, 15, 20, 25, 30] {
+            assert!(set.contains(num), "{} should be in the set", num);
+        }
+        for num in [0, 6, 9, 16, 19, 31, 100] {
+            assert!(!set.contains(num), "{} shouldn't be in the set", num);
+        }
+        assert!(!IntervalSet::new().contains(7));
+    }
+
+    #[test]
+    fn gaps() {
+        let set: IntervalSet<u32> = [1..=5, 10..=15, 17..=30].into_iter().collect();
+        assert_eq!(set.gaps().collect::<Vec<_>>(), vec![6..=9, 16..=16]);
+        assert_eq!(IntervalSet::<u32>::new().gaps().count(), 0);
+    }
+
+    #[test]
+    fn set_operations() {
+        let a: IntervalSet<u32> = [1..=5, 10..=20].into_iter().collect();
+        let b: IntervalSet<u32> = [4..=12, 18..=25].into_iter().collect();
+
+        assert_eq!(bounds(&a.union(&b)), vec![(1, 25)]);
+        assert_eq!(bounds(&a.intersection(&b)), vec![(4, 5), (10, 12), (18, 20)]);
+        assert_eq!(bounds(&a.difference(&b)), vec![(1, 3), (13, 17)]);
+        assert_eq!(bounds(&b.difference(&a)), vec![(6, 9), (21, 25)]);
+    }
+
+    #[test]
+    fn debug_looks_like_the_input() {
+        let set: IntervalSet<u32> = [1..=5, 10..=15].into_iter().collect();
+        assert_eq!(format!("{:?}", set), "[1-5, 10-15]");
+    }
+
+    // Property tests. These make lots of random operations on u8s (see
+    // rng.rs), and then check the IntervalSet against a BTreeSet holding
+    // every value. u8 is small enough to check every possible value each
+    // time and it makes it easy to hit 0 and 255 where successor/predecessor
+    // run out.
+
+    fn random_range(rng: &mut Rng) -> RangeInclusive<u8> {
+        let start = rng.next_u64() as u8;
+        // mostly short ranges so there are plenty of gaps to merge into
+        let len = rng.below(24) as u8;
+        start..=start.saturating_add(len)
+    }
+
+    fn brute_force(range: RangeInclusive<u8>) -> BTreeSet<u8> {
+        range.collect()
+    }
+
+    fn values(set: &IntervalSet<u8>) -> BTreeSet<u8> {
+        set.iter().flatten().collect()
+    }
+
+    fn random_set(rng: &mut Rng) -> (IntervalSet<u8>, BTreeSet<u8>) {
+        let mut set = IntervalSet::new();
+        let mut model = BTreeSet::new();
+
+        for _ in 0..rng.below(12) {
+            let range = random_range(rng);
+            if rng.next_u64().is_multiple_of(3) {
+                set.remove(range.clone());
+                model = &model - &brute_force(range);
+            } else {
+                set.insert(range.clone());
+                model.extend(range);
+            }
+        }
+
+        (set, model)
+    }
+
+    /// Everything that should always be true about a set
+    fn check(set: &IntervalSet<u8>, model: &BTreeSet<u8>) {
+        assert_eq!(values(set), *model);
+        assert_eq!(set.len(), model.len() as u128);
+
+        for pair in set.ranges.windows(2) {
+            assert!(pair[0].0 <= pair[0].1);
+            assert!(!touches(pair[0].1, pair[1].0), "{:?} has touching ranges", set);
+        }
+
+        for val in 0..=u8::MAX {
+            assert_eq!(set.contains(val), model.contains(&val), "contains({}) for {:?}", val, set);
+        }
+
+        // the gaps are everything between the first and last value that's missing
+        let gaps: BTreeSet<u8> = set.gaps().flatten().collect();
+        let expected_gaps: BTreeSet<u8> = match (model.first(), model.last()) {
+            (Some(&first), Some(&last)) => (first..=last).filter(|v| !model.contains(v)).collect(),
+            _ => BTreeSet::new(),
+        };
+        assert_eq!(gaps, expected_gaps);
+    }
+
+    #[test]
+    fn random_inserts_and_removes_match_a_btreeset() {
+        cases(0x9e3779b97f4a7c15, 500, |rng| {
+            let (set, model) = random_set(rng);
+            check(&set, &model);
+        });
+    }
+
+    #[test]
+    fn random_set_operations_match_a_btreeset() {
+        cases(0x2545f4914f6cdd1d, 500, |rng| {
+            let (a, a_model) = random_set(rng);
+            let (b, b_model) = random_set(rng);
+
+            check(&a.union(&b), &(&a_model | &b_model));
+            check(&a.intersection(&b), &(&a_model & &b_model));
+            check(&a.difference(&b), &(&a_model - &b_model));
+        });
+    }
+}
@@ -0,0 +1,396 @@
+use std::cmp::Ordering;
+use std::ops::RangeInclusive;
+
+use crate::interval::{touches, Step};
+
+// This is the fancy BST where every node is a range of numbers that the
+// notes at the top of day5part1.rs wanted. The plain Tree in day5part01.rs
+// falls over into a linked list if the values come in sorted, so this one
+// is an AVL tree: every node remembers its height, and after every insert
+// or remove the nodes on the way back up get rotated so the two sides
+// never differ in height by more than 1. That keeps everything O(log n).
+//
+// Ranges get merged on insert the same way IntervalSet does it, so the
+// tree only ever holds ranges that don't overlap or touch. A classic
+// interval tree also keeps the biggest end in each subtree so it can
+// handle overlapping ranges, but once the ranges are merged sorting them
+// by start sorts them by end too, so the start is all we need to search on.
+
+type Link<T> = Option<Box<Node<T>>>;
+
+struct Node<T> {
+    start: T,
+    end: T,
+    height: usize,
+    left: Link<T>,
+    right: Link<T>,
+}
+
+impl<T: Step> Node<T> {
+    fn new(start: T, end: T) -> Box<Self> {
+        Box::new(Node { start, end, height: 1, left: None, right: None })
+    }
+}
+
+fn height<T>(link: &Link<T>) -> usize {
+    link.as_ref().map(|node| node.height).unwrap_or(0)
+}
+
+/// Fix up a node's height after one of its children changed
+fn update<T>(node: &mut Node<T>) {
+    node.height = 1 + height(&node.left).max(height(&node.right));
+}
+
+/// Left height minus right height. AVL keeps this between -1 and 1.
+fn balance_factor<T>(node: &Node<T>) -> isize {
+    height(&node.left) as isize - height(&node.right) as isize
+}
+
+//     node            left
+//     /  \            /  \
+//   left  c   ->     a   node
+//   /  \                 /  \
+//  a    b               b    c
+fn rotate_right<T>(mut node: Box<Node<T>>) -> Box<Node<T>> {
+    let mut left = node.left.take().expect("rotate_right needs a left child");
+    node.left = left.right.take();
+    update(&mut node);
+    left.right = Some(node);
+    update(&mut left);
+    left
+}
+
+// The mirror image of rotate_right
+fn rotate_left<T>(mut node: Box<Node<T>>) -> Box<Node<T>> {
+    let mut right = node.right.take().expect("rotate_left needs a right child");
+    node.right = right.left.take();
+    update(&mut node);
+    right.left = Some(node);
+    update(&mut right);
+    right
+}
+
+/// Put a node that might be off by 2 back into balance. The zig-zag
+/// cases (left child leaning right or vice versa) need two rotations.
+fn rebalance<T>(mut node: Box<Node<T>>) -> Box<Node<T>> {
+    update(&mut node);
+
+    match balance_factor(&node) {
+        2 => {
+            if balance_factor(node.left.as_ref().unwrap()) < 0 {
+                node.left = Some(rotate_left(node.left.take().unwrap()));
+            }
+            rotate_right(node)
+        }
+        -2 => {
+            if balance_factor(node.right.as_ref().unwrap()) > 0 {
+                node.right = Some(rotate_right(node.right.take().unwrap()));
+            }
+            rotate_left(node)
+        }
+        _ => node,
+    }
+}
+
+/// Insert a range that doesn't touch anything already in the tree
+fn insert_node<T: Step>(link: Link<T>, start: T, end: T) -> Box<Node<T>> {
+    let Some(mut node) = link else {
+        return Node::new(start, end);
+    };
+
+    if start < node.start {
+        node.left = Some(insert_node(node.left.take(), start, end));
+    } else {
+        node.right = Some(insert_node(node.right.take(), start, end));
+    }
+
+    rebalance(node)
+}
+
+/// Take the smallest node out of a subtree, handing back what's left of
+/// the subtree and the node that was removed
+fn remove_min<T>(mut node: Box<Node<T>>) -> (Link<T>, Box<Node<T>>) {
+    match node.left.take() {
+        None => (node.right.take(), node),
+        Some(left) => {
+            let (rest, min) = remove_min(left);
+            node.left = rest;
+            (Some(rebalance(node)), min)
+        }
+    }
+}
+
+/// Remove the node whose range starts at `start`, if there is one
+fn remove_node<T: Step>(link: Link<T>, start: T) -> Link<T> {
+    let mut node = link?;
+
+    match start.cmp(&node.start) {
+        Ordering::Less => node.left = remove_node(node.left.take(), start),
+        Ordering::Greater => node.right = remove_node(node.right.take(), start),
+        Ordering::Equal => {
+            // with at most one child the child just moves up. With two,
+            // the smallest thing on the right takes this node's place
+            return match (node.left.take(), node.right.take()) {
+                (None, child) | (child, None) => child,
+                (Some(left), Some(right)) => {
+                    let (rest, mut successor) = remove_min(right);
+                    successor.left = Some(left);
+                    successor.right = rest;
+                    Some(rebalance(successor))
+                }
+            };
+        }
+    }
+
+    Some(rebalance(node))
+}
+
+/// A set of values stored as merged ranges in an AVL tree. It does the
+/// same job as IntervalSet, just with a tree instead of a sorted Vec.
+pub struct IntervalTree<T> {
+    root: Link<T>,
+}
+
+impl<T: Step> IntervalTree<T> {
+    pub fn new() -> Self {
+        IntervalTree { root: None }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    /// How tall the tree is, 0 when it's empty
+    pub fn height(&self) -> usize {
+        height(&self.root)
+    }
+
+    /// Any range in the tree that overlaps or touches start..=end
+    fn find_touching(&self, start: T, end: T) -> Option<(T, T)> {
+        let mut link = &self.root;
+
+        while let Some(node) = link {
+            if !touches(end, node.start) {
+                // this node (and everything right of it) starts too late
+                link = &node.left;
+            } else if !touches(node.end, start) {
+                // this node (and everything left of it) ends too early
+                link = &node.right;
+            } else {
+                return Some((node.start, node.end));
+            }
+        }
+
+        None
+    }
+
+    /// Add a range to the tree. Anything already in the tree that it
+    /// overlaps or touches gets pulled out and merged into it first, so
+    /// the tree never has two ranges that should be one.
+    pub fn insert(&mut self, range: RangeInclusive<T>) {
+        let (mut start, mut end) = range.into_inner();
+        if start > end {
+            return;
+        }
+
+        while let Some((r_start, r_end)) = self.find_touching(start, end) {
+            self.root = remove_node(self.root.take(), r_start);
+            start = start.min(r_start);
+            end = end.max(r_end);
+        }
+
+        self.root = Some(insert_node(self.root.take(), start, end));
+    }
+
+    /// The range that contains the value, if any (aka a stabbing query)
+    pub fn stab(&self, val: T) -> Option<RangeInclusive<T>> {
+        let mut link = &self.root;
+
+        while let Some(node) = link {
+            if val < node.start {
+                link = &node.left;
+            } else if val > node.end {
+                link = &node.right;
+            } else {
+                return Some(node.start..=node.end);
+            }
+        }
+
+        None
+    }
+
+    /// Whether the value is in any of the ranges
+    pub fn contains(&self, val: T) -> bool {
+        self.stab(val).is_some()
+    }
+
+    /// Every range in the tree that shares at least one value with the
+    /// given range, smallest first
+    pub fn overlapping(&self, range: RangeInclusive<T>) -> Vec<RangeInclusive<T>> {
+        let mut found = vec![];
+        if range.start() <= range.end() {
+            collect_overlapping(&self.root, *range.start(), *range.end(), &mut found);
+        }
+        found
+    }
+
+    /// The merged ranges, smallest first
+    pub fn iter(&self) -> Iter<'_, T> {
+        let mut iter = Iter { stack: vec![] };
+        iter.push_left(&self.root);
+        iter
+    }
+
+    /// How many values are in the tree (not how many ranges)
+    pub fn len(&self) -> u128 {
+        self.iter().map(|range| T::steps_between(*range.start(), *range.end()) + 1).sum()
+    }
+}
+
+/// In order walk that skips any subtree that can't overlap start..=end
+fn collect_overlapping<T: Step>(link: &Link<T>, start: T, end: T, found: &mut Vec<RangeInclusive<T>>) {
+    let Some(node) = link else {
+        return;
+    };
+
+    if start < node.start {
+        collect_overlapping(&node.left, start, end, found);
+    }
+    if node.start <= end && node.end >= start {
+        found.push(node.start..=node.end);
+    }
+    if end > node.end {
+        collect_overlapping(&node.right, start, end, found);
+    }
+}
+
+impl<T: Step> Default for IntervalTree<T> {
+    fn default() -> Self {
+        IntervalTree::new()
+    }
+}
+
+impl<T: Step> FromIterator<RangeInclusive<T>> for IntervalTree<T> {
+    fn from_iter<I: IntoIterator<Item = RangeInclusive<T>>>(iter: I) -> Self {
+        let mut tree = IntervalTree::new();
+        for range in iter {
+            tree.insert(range);
+        }
+        tree
+    }
+}
+
+/// In order iterator over the ranges in an IntervalTree. It keeps a stack
+/// of the nodes whose left side has been visited but they haven't yet.
+pub struct Iter<'a, T> {
+    stack: Vec<&'a Node<T>>,
+}
+
+impl<'a, T> Iter<'a, T> {
+    fn push_left(&mut self, mut link: &'a Link<T>) {
+        while let Some(node) = link {
+            self.stack.push(node);
+            link = &node.left;
+        }
+    }
+}
+
+impl<'a, T: Step> Iterator for Iter<'a, T> {
+    type Item = RangeInclusive<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.stack.pop()?;
+        self.push_left(&node.right);
+        Some(node.start..=node.end)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interval::IntervalSet;
+    use crate::rng::cases;
+
+    fn bounds<T: Step>(tree: &IntervalTree<T>) -> Vec<(T, T)> {
+        tree.iter().map(|r| (*r.start(), *r.end())).collect()
+    }
+
+    /// Every node is balanced and has the right height
+    fn check_balanced<T>(link: &Link<T>) -> usize {
+        let Some(node) = link else {
+            return 0;
+        };
+        let left = check_balanced(&node.left);
+        let right = check_balanced(&node.right);
+        assert!(left.abs_diff(right) <= 1, "tree is out of balance");
+        assert_eq!(node.height, 1 + left.max(right));
+        node.height
+    }
+
+    #[test]
+    fn insert_merges() {
+        let tree: IntervalTree<u32> = [1..=3, 8..=9, 4..=5, 20..=25, 7..=7].into_iter().collect();
+        assert_eq!(bounds(&tree), vec![(1, 5), (7, 9), (20, 25)]);
+
+        let tree: IntervalTree<u32> = [1..=3, 5..=6, 8..=9, 11..=13, 4..=12].into_iter().collect();
+        assert_eq!(bounds(&tree), vec![(1, 13)]);
+    }
+
+    #[test]
+    fn sorted_inserts_stay_balanced() {
+        // a plain BST would be 1000 deep after this
+        let tree: IntervalTree<u32> = (0..1000).map(|i| i * 10..=i * 10 + 5).collect();
+        check_balanced(&tree.root);
+        assert!(tree.height() <= 15, "height was {}", tree.height());
+        assert_eq!(tree.iter().count(), 1000);
+    }
+
+    #[test]
+    fn stab() {
+        let tree: IntervalTree<usize> = [1..=5, 10..=15, 20..=30].into_iter().collect();
+        assert_eq!(tree.stab(12), Some(10..=15));
+        assert_eq!(tree.stab(30), Some(20..=30));
+        assert_eq!(tree.stab(16), None);
+        assert!(tree.contains(1));
+        assert!(!tree.contains(0));
+        assert!(!IntervalTree::new().contains(7));
+    }
+
+    #[test]
+    fn overlapping() {
+        let tree: IntervalTree<usize> = [1..=5, 10..=15, 20..=30, 40..=41].into_iter().collect();
+        assert_eq!(tree.overlapping(4..=20), vec![1..=5, 10..=15, 20..=30]);
+        assert_eq!(tree.overlapping(6..=9), vec![]);
+        assert_eq!(tree.overlapping(41..=100), vec![40..=41]);
+    }
+
+    // Throw the same random ranges at a tree and an IntervalSet (which has
+    // its own property tests against a BTreeSet) and make sure they agree
+    #[test]
+    fn random_inserts_match_interval_set() {
+        cases(0x9e3779b97f4a7c15, 200, |rng| {
+            let mut tree = IntervalTree::new();
+            let mut set = IntervalSet::new();
+
+            for _ in 0..rng.below(40) {
+                let start = rng.below(1000) as u32;
+                let range = start..=start + rng.below(30) as u32;
+                tree.insert(range.clone());
+                set.insert(range);
+            }
+
+            check_balanced(&tree.root);
+            assert_eq!(tree.iter().collect::<Vec<_>>(), set.iter().collect::<Vec<_>>());
+            assert_eq!(tree.len(), set.len());
+
+            for _ in 0..20 {
+                let val = rng.below(1050) as u32;
+                assert_eq!(tree.contains(val), set.contains(val));
+
+                let query = val..=val + rng.below(50) as u32;
+                let expected: Vec<_> = set.iter().filter(|r| r.start() <= query.end() && r.end() >= query.start()).collect();
+                assert_eq!(tree.overlapping(query), expected);
+            }
+        });
+    }
+}
@@ -0,0 +1,36 @@
+// All of the day/part modules live in the library so that the runner
+// in main.rs (and anything else that wants to) can look them up through
+// the solver registry instead of a hand-written match.
+
+pub mod answer;
+pub mod bench;
+pub mod dial;
+pub mod error;
+pub mod expected;
+pub mod grid;
+pub mod interval;
+pub mod interval_tree;
+pub mod neighborhood;
+pub mod rmq;
+pub mod rng;
+pub mod runner;
+pub mod solver;
+
+pub mod day1part1;
+pub mod day1part2;
+pub mod day1starts;
+pub mod day2check;
+pub mod day2part1;
+pub mod day2part2;
+pub mod day3part1;
+pub mod day3part2;
+pub mod day4part1;
+pub mod day4part2;
+pub mod day5part01;
+pub mod day5part1;
+pub mod day5part2;
+pub mod day6part1;
+pub mod day6part2;
+pub mod day7part1;
+pub mod day7part2;
+pub mod day9part1;
@@ -1,58 +1,546 @@
 use std::env;
-use std::fs;
-
-mod day1part1;
-mod day1part2;
-mod day2part1;
-mod day2part2;
-mod day3part1;
-mod day3part2;
-mod day4part1;
-mod day4part2;
-mod day5part01;
-mod day5part1;
-mod day5part2;
-mod day6part1;
-mod day6part2;
-mod day7part1;
-mod day7part2;
-mod day9part1;
-
-fn main() {
-    let args: Vec<String> = env::args().collect();
-
-    if args.len() != 3 {
-        println!("Requires a day-part argument e.g. cargo run -- 1-1");
-        return
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use aoc::bench;
+use aoc::day1part1::parse_instructions;
+use aoc::day1starts;
+use aoc::day2part1::parse_ranges;
+use aoc::day2check;
+use aoc::expected::{self, ExpectedAnswers, Verdict};
+use aoc::neighborhood::{Edges, Rules, Shape, MAX_RADIUS};
+use aoc::runner::{self, InputSource};
+use aoc::solver::{OutputFormat, Registry, ReportOptions};
+
+const USAGE: &str = "Usage:
+  cargo run -- <day-part> [--test] [--input <path>] [--length N] [--report [--limit N]] [--format table|csv|json]
+                      [--shape moore|von-neumann] [--radius N] [--offsets \"r,c r,c ...\"] [--threshold N] [--wrap]
+                                                      e.g. cargo run -- 1-2 --test
+  cargo run -- <day-part> <path>                      e.g. cargo run -- 1-2 src/inputs/day1.txt
+  cargo run -- run <all|day|day-part..day-part> [--test]
+  cargo run --release -- bench <all|day|day-part..day-part> [--test] [--iterations N] [--format table|csv|json]
+  cargo run -- verify
+  cargo run -- crosscheck [--test] [--input <path>]
+  cargo run -- crosscheck random [count] [--seed N]
+  cargo run -- starts [password] [--test] [--input <path>] [--format table|csv|json]
+  cargo run -- list
+
+By default the input is read from src/inputs/dayN.txt. --test reads
+dayN-test.txt instead, and an input path of - reads from stdin. --length
+changes how many digits day 3 part 2 picks from each bank. --report shows
+how the answer was put together for days that can explain themselves (like
+which batteries day 3 turned on in each bank), and --limit N cuts long
+lists in a report (like day 2's invalid IDs) down to the first N (day 2
+lists 20 if it isn't given).
+
+The day 4 rules can be changed too. --shape and --radius pick which cells
+around a roll count as neighbors (moore 1 is the 8 around it, von-neumann 1
+is the 4 that share an edge, and the radius tops out at 150), or --offsets
+lists them by hand. --threshold N makes a roll reachable with fewer than N
+rolls around it, and --wrap makes the edges of the floor wrap around.
+Anything left out is the same as the puzzle.
+
+crosscheck runs every day 2 strategy against checking each ID one at a time,
+either on the day 2 input or on random ranges, and shows any range where
+they disagree.
+
+starts works out both day 1 passwords for every position the dial could
+start at, and with a password shows only the starts that give it.";
+
+/// Print every solver we know about, one per line
+fn print_solvers(registry: &Registry) {
+    for solver in registry.iter() {
+        println!("{:<12} {}", solver.key(), solver.name());
+    }
+}
+
+/// The command line after the flags have been pulled out of it
+struct Options {
+    test: bool,
+    input: Option<String>,
+    iterations: Option<u32>,
+    format: Option<OutputFormat>,
+    length: Option<usize>,
+    report: bool,
+    limit: Option<usize>,
+    seed: Option<u64>,
+    shape: Option<String>,
+    radius: Option<usize>,
+    offsets: Option<Shape>,
+    threshold: Option<usize>,
+    wrap: bool,
+    positional: Vec<String>,
+}
+
+/// Flags can go anywhere, everything else is positional
+fn parse_args(args: &[String]) -> Result<Options, String> {
+    let mut options = Options { test: false, input: None, iterations: None, format: None, length: None, report: false, limit: None, seed: None, shape: None, radius: None, offsets: None, threshold: None, wrap: false, positional: vec![] };
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--test" => options.test = true,
+            "--report" => options.report = true,
+            "--input" => {
+                let Some(path) = args.next() else {
+                    return Err("--input needs a path".to_string());
+                };
+                options.input = Some(path.clone());
+            }
+            "--iterations" => {
+                let Some(Ok(iterations)) = args.next().map(|n| n.parse()) else {
+                    return Err("--iterations needs a number".to_string());
+                };
+                options.iterations = Some(iterations);
+            }
+            "--length" => {
+                let Some(Ok(length)) = args.next().map(|n| n.parse::<usize>()) else {
+                    return Err("--length needs a number".to_string());
+                };
+                if length == 0 {
+                    return Err("--length needs to be at least 1".to_string());
+                }
+                options.length = Some(length);
+            }
+            "--limit" => {
+                let Some(Ok(limit)) = args.next().map(|n| n.parse()) else {
+                    return Err("--limit needs a number".to_string());
+                };
+                options.limit = Some(limit);
+            }
+            "--seed" => {
+                let Some(Ok(seed)) = args.next().map(|n| n.parse()) else {
+                    return Err("--seed needs a number".to_string());
+                };
+                options.seed = Some(seed);
+            }
+            "--shape" => {
+                let Some(shape) = args.next().filter(|name| Shape::parse(name, 1).is_some()) else {
+                    return Err("--shape needs to be moore or von-neumann".to_string());
+                };
+                options.shape = Some(shape.clone());
+            }
+            "--radius" => {
+                let Some(Ok(radius)) = args.next().map(|n| n.parse()) else {
+                    return Err("--radius needs a number".to_string());
+                };
+                options.radius = Some(radius);
+            }
+            "--offsets" => {
+                let Some(offsets) = args.next().and_then(|text| Shape::parse_offsets(text)) else {
+                    return Err("--offsets needs row,col pairs separated by spaces e.g. \"-1,0 0,1\"".to_string());
+                };
+                options.offsets = Some(offsets);
+            }
+            "--threshold" => {
+                let Some(Ok(threshold)) = args.next().map(|n| n.parse()) else {
+                    return Err("--threshold needs a number".to_string());
+                };
+                options.threshold = Some(threshold);
+            }
+            "--wrap" => options.wrap = true,
+            "--format" => {
+                let Some(format) = args.next().and_then(|f| OutputFormat::parse(f)) else {
+                    return Err("--format needs to be table, csv, or json".to_string());
+                };
+                options.format = Some(format);
+            }
+            _ => options.positional.push(arg.clone()),
+        }
+    }
+
+    Ok(options)
+}
+
+/// The neighborhood rules asked for on the command line, starting from the
+/// puzzle's and changing whatever was given. None if nothing was.
+fn neighborhood_rules(options: &Options) -> Result<Option<Rules>, String> {
+    if options.shape.is_none() && options.radius.is_none() && options.offsets.is_none() && options.threshold.is_none() && !options.wrap {
+        return Ok(None);
+    }
+
+    let mut rules = Rules::puzzle();
+    match &options.offsets {
+        Some(_) if options.shape.is_some() || options.radius.is_some() => {
+            return Err("--offsets can't be used with --shape or --radius".to_string());
+        }
+        Some(offsets) => rules.shape = offsets.clone(),
+        None => {
+            let radius = options.radius.unwrap_or(1);
+            if radius > MAX_RADIUS {
+                return Err(format!("--radius can't be more than {}", MAX_RADIUS));
+            }
+            let name = options.shape.as_deref().unwrap_or("moore");
+            // parse_args already made sure the name is one we know
+            rules.shape = Shape::parse(name, radius).unwrap();
+        }
+    }
+    if let Some(threshold) = options.threshold {
+        rules.threshold = threshold;
+    }
+    if options.wrap {
+        rules.edges = Edges::Wrap;
+    }
+
+    Ok(Some(rules))
+}
+
+/// Work out where the input should come from. An explicit path (either
+/// with --input or the old style second argument) wins, then --test,
+/// otherwise it's src/inputs/dayN.txt
+fn input_source(options: &Options, path: Option<&String>) -> Result<InputSource, String> {
+    let path = match (&options.input, path) {
+        (Some(_), Some(_)) => return Err("give the input path either with --input or after the day-part, not both".to_string()),
+        (Some(path), None) | (None, Some(path)) => path,
+        (None, None) => return Ok(InputSource::Default { test: options.test }),
+    };
+
+    if options.test {
+        return Err("--test can't be used with an input path".to_string());
+    }
+
+    if path == "-" {
+        Ok(InputSource::Stdin)
+    } else {
+        Ok(InputSource::Path(PathBuf::from(path)))
+    }
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let registry = Registry::default();
+
+    let options = match parse_args(&args) {
+        Ok(options) => options,
+        Err(e) => {
+            println!("{}\n\n{}", e, USAGE);
+            return ExitCode::FAILURE
+        }
+    };
+
+    let positional: Vec<&str> = options.positional.iter().map(|s| s.as_str()).collect();
+
+    // Every command that isn't solving a single day-part. Flags that only
+    // mean something for one day-part get turned down for all of these
+    // rather than quietly ignored.
+    let not_a_daypart = matches!(positional.as_slice(), ["list"] | ["verify"] | ["run", _] | ["bench", _] | ["crosscheck", ..] | ["starts", ..]);
+
+    if options.length.is_some() && not_a_daypart {
+        println!("--length only works when solving a single day-part");
+        return ExitCode::FAILURE
+    }
+
+    let rules = match neighborhood_rules(&options) {
+        Ok(rules) => rules,
+        Err(e) => {
+            println!("{}\n\n{}", e, USAGE);
+            return ExitCode::FAILURE
+        }
     };
 
-    let daypart: String = args[1].clone();
-    let input_file: String = args[2].clone();
-
-    // Read the file. I feel like I've been burned in the past
-    // by different files needing different parsing, so just
-    // read for now and let each day do its own parsing. 
-    let content: String = fs::read_to_string(input_file)
-        .expect("Should have been able to read the file");
-
-    // Choose the right code and run it!
-    match daypart.as_str() {
-        "1-1" => day1part1::solve(&content),
-        "1-2" => day1part2::solve(&content),
-        "2-1" => day2part1::solve(&content),
-        "2-2" => day2part2::solve(&content),
-        "3-1" => day3part1::solve(&content),
-        "3-2" => day3part2::solve(&content),
-        "4-1" => day4part1::solve(&content),
-        "4-2" => day4part2::solve(&content),
-        "5-01" => day5part01::solve(&content),
-        "5-1" => day5part1::solve(&content),
-        "5-2" => day5part2::solve(&content),
-        "6-1" => day6part1::solve(&content),
-        "6-2" => day6part2::solve(&content),
-        "7-1" => day7part1::solve(&content),
-        "7-2" => day7part2::solve(&content),
-        "9-1" => day9part1::solve(&content),
-        _ => panic!("Unknown day/part")
+    if rules.is_some() && not_a_daypart {
+        println!("the day 4 rules only work when solving a single day-part");
+        return ExitCode::FAILURE
+    }
+
+    if options.report && not_a_daypart {
+        println!("--report only works when solving a single day-part");
+        return ExitCode::FAILURE
+    }
+
+    // run and bench always read from src/inputs, and the rest don't read
+    // anything at all
+    if options.input.is_some() && matches!(positional.as_slice(), ["list"] | ["verify"] | ["run", _] | ["bench", _] | ["crosscheck", "random", ..]) {
+        println!("--input only works when solving a single day-part, crosscheck, or starts");
+        return ExitCode::FAILURE
+    }
+
+    if options.test && matches!(positional.as_slice(), ["list"] | ["verify"] | ["crosscheck", "random", ..]) {
+        println!("--test doesn't do anything for list, verify, or crosscheck random");
+        return ExitCode::FAILURE
+    }
+
+    if options.iterations.is_some() && !matches!(positional.as_slice(), ["bench", _]) {
+        println!("--iterations only works with bench");
+        return ExitCode::FAILURE
+    }
+
+    if options.format.is_some() && !options.report && !matches!(positional.as_slice(), ["bench", _] | ["starts", ..]) {
+        println!("--format only works with bench, starts, or --report");
+        return ExitCode::FAILURE
+    }
+
+    if options.limit.is_some() && !options.report {
+        println!("--limit only works with --report");
+        return ExitCode::FAILURE
+    }
+
+    if options.seed.is_some() && !matches!(positional.as_slice(), ["crosscheck", "random", ..]) {
+        println!("--seed only works with crosscheck random");
+        return ExitCode::FAILURE
+    }
+
+    match positional.as_slice() {
+        ["list"] => {
+            print_solvers(&registry);
+            ExitCode::SUCCESS
+        }
+
+        // Check every solver against the answers in src/inputs/answers.toml
+        ["verify"] => {
+            let expected = match ExpectedAnswers::load() {
+                Ok(expected) => expected,
+                Err(e) => {
+                    println!("{}", e);
+                    return ExitCode::FAILURE
+                }
+            };
+
+            let results = expected::verify(&registry, &expected);
+            expected::print_verifications(&results);
+
+            // missing answers are fine, wrong ones (or ones that blew up) aren't
+            if results.iter().any(|r| matches!(r.verdict, Verdict::Fail { .. } | Verdict::Error(_))) {
+                return ExitCode::FAILURE
+            }
+            ExitCode::SUCCESS
+        }
+
+        // Make sure all of the ways of doing day 2 agree with brute force
+        ["crosscheck"] => {
+            let input = match input_source(&options, None) {
+                Ok(input) => input,
+                Err(e) => {
+                    println!("{}", e);
+                    return ExitCode::FAILURE
+                }
+            };
+            let content = match input.read(2) {
+                Ok(content) => content,
+                Err(e) => {
+                    println!("{}", e);
+                    return ExitCode::FAILURE
+                }
+            };
+
+            // count the ranges the same way they get parsed, so a trailing
+            // comma or newline doesn't turn into an extra range
+            match parse_ranges(&content).and_then(|ranges| Ok((ranges.len(), day2check::check_input(&content)?))) {
+                Ok((range_count, disagreements)) => {
+                    day2check::print_disagreements(&disagreements, range_count);
+                    if disagreements.is_empty() { ExitCode::SUCCESS } else { ExitCode::FAILURE }
+                }
+                Err(e) => {
+                    println!("crosscheck: couldn't parse the input, {}", e);
+                    ExitCode::FAILURE
+                }
+            }
+        }
+
+        ["crosscheck", "random", rest @ ..] if rest.len() <= 1 => {
+            let count = match rest.first().map(|n| n.parse::<usize>()) {
+                None => 1000,
+                Some(Ok(count)) => count,
+                Some(Err(_)) => {
+                    println!("the number of random ranges needs to be a number\n\n{}", USAGE);
+                    return ExitCode::FAILURE
+                }
+            };
+
+            // a new seed every time unless one is given, and it's printed
+            // so a failure can be run again
+            let seed = options.seed.unwrap_or_else(|| SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_nanos() as u64).unwrap_or(1));
+            println!("seed {}", seed);
+
+            let disagreements = day2check::check_random(seed, count);
+            day2check::print_disagreements(&disagreements, count);
+            if disagreements.is_empty() { ExitCode::SUCCESS } else { ExitCode::FAILURE }
+        }
+
+        // Both day 1 passwords for every starting position
+        ["starts", rest @ ..] if rest.len() <= 1 => {
+            let target = match rest.first().map(|n| n.parse::<u128>()) {
+                None => None,
+                Some(Ok(target)) => Some(target),
+                Some(Err(_)) => {
+                    println!("the password needs to be a number\n\n{}", USAGE);
+                    return ExitCode::FAILURE
+                }
+            };
+
+            let input = match input_source(&options, None) {
+                Ok(input) => input,
+                Err(e) => {
+                    println!("{}", e);
+                    return ExitCode::FAILURE
+                }
+            };
+            let content = match input.read(1) {
+                Ok(content) => content,
+                Err(e) => {
+                    println!("{}", e);
+                    return ExitCode::FAILURE
+                }
+            };
+
+            match parse_instructions(&content).and_then(|instructions| day1starts::every_puzzle_start(&instructions)) {
+                Ok(results) => {
+                    println!("{}", day1starts::format_starts(&results, target, options.format.unwrap_or(OutputFormat::Table)));
+                    ExitCode::SUCCESS
+                }
+                Err(e) => {
+                    println!("starts: couldn't parse the input, {}", e);
+                    ExitCode::FAILURE
+                }
+            }
+        }
+
+        // Run a bunch of solvers on their inputs e.g. `run all`, `run 4`, or
+        // `run 4-1..7-2` and print a table of the answers
+        ["run", selection] => {
+            let solvers = match runner::select(&registry, selection, false) {
+                Ok(solvers) => solvers,
+                Err(e) => {
+                    println!("{}", e);
+                    return ExitCode::FAILURE
+                }
+            };
+
+            let input = InputSource::Default { test: options.test };
+            let results: Vec<_> = solvers.into_iter().map(|solver| runner::run(solver, &input)).collect();
+            runner::print_summary(&results);
+
+            if results.iter().any(|r| r.outcome.is_err()) {
+                return ExitCode::FAILURE
+            }
+            ExitCode::SUCCESS
+        }
+
+        // Time parsing and solving for a bunch of solvers
+        ["bench", selection] => {
+            // bench times the alternative versions too, since comparing
+            // them is the whole point
+            let solvers = match runner::select(&registry, selection, true) {
+                Ok(solvers) => solvers,
+                Err(e) => {
+                    println!("{}", e);
+                    return ExitCode::FAILURE
+                }
+            };
+
+            let input = InputSource::Default { test: options.test };
+            let mut results = vec![];
+            for solver in solvers {
+                let bench_result = input
+                    .read(solver.day())
+                    .and_then(|content| bench::bench(solver, &content, options.iterations.unwrap_or(10)).map_err(|e| e.to_string()));
+
+                match bench_result {
+                    Ok(result) => results.push(result),
+                    Err(e) => {
+                        println!("{}: {}", solver.key(), e);
+                        return ExitCode::FAILURE
+                    }
+                }
+            }
+
+            bench::print_results(&results, options.format.unwrap_or(OutputFormat::Table));
+            ExitCode::SUCCESS
+        }
+
+        [daypart] | [daypart, _] => {
+            // Find the right code to run before bothering to read the input
+            let Some(solver) = registry.get(daypart) else {
+                println!("Unknown day/part: {}. Available day/parts are:", daypart);
+                print_solvers(&registry);
+                return ExitCode::FAILURE
+            };
+
+            // swap in a copy of the solver with a different length if
+            // one was asked for (and the solver has a length to change)
+            let configured;
+            let solver = match options.length {
+                Some(length) => match solver.with_length(length) {
+                    Some(with_length) => {
+                        configured = with_length;
+                        configured.as_ref()
+                    }
+                    None => {
+                        println!("{} doesn't have a length to change", solver.key());
+                        return ExitCode::FAILURE
+                    }
+                },
+                None => solver,
+            };
+
+            // and the same again for the neighborhood rules
+            let with_rules;
+            let solver = match &rules {
+                Some(rules) => match solver.with_rules(rules) {
+                    Some(configured) => {
+                        with_rules = configured;
+                        with_rules.as_ref()
+                    }
+                    None => {
+                        println!("{} doesn't have neighborhood rules to change", solver.key());
+                        return ExitCode::FAILURE
+                    }
+                },
+                None => solver,
+            };
+
+            let input = match input_source(&options, options.positional.get(1)) {
+                Ok(input) => input,
+                Err(e) => {
+                    println!("{}", e);
+                    return ExitCode::FAILURE
+                }
+            };
+
+            // Read the input. I feel like I've been burned in the past
+            // by different files needing different parsing, so just
+            // read for now and let each day do its own parsing.
+            let content = match input.read(solver.day()) {
+                Ok(content) => content,
+                Err(e) => {
+                    println!("{}", e);
+                    return ExitCode::FAILURE
+                }
+            };
+
+            // Show how the answer was put together first, if asked
+            if options.report {
+                let report_options = ReportOptions { format: options.format.unwrap_or(OutputFormat::Table), limit: options.limit };
+                match solver.report(&content, report_options) {
+                    Some(Ok(report)) => println!("{}", report),
+                    Some(Err(e)) => {
+                        println!("{}: couldn't parse the input, {}", solver.key(), e);
+                        return ExitCode::FAILURE
+                    }
+                    None => {
+                        println!("{} doesn't have a report", solver.key());
+                        return ExitCode::FAILURE
+                    }
+                }
+            }
+
+            // Run it! Every solver hands back its answer so they all get
+            // printed the same way.
+            match solver.solve(&content) {
+                Ok(answer) => println!("{} ({}): {}", solver.key(), solver.name(), answer),
+                Err(e) => {
+                    println!("{}: couldn't parse the input, {}", solver.key(), e);
+                    return ExitCode::FAILURE
+                }
+            }
+
+            ExitCode::SUCCESS
+        }
+
+        _ => {
+            println!("{}", USAGE);
+            ExitCode::FAILURE
+        }
     }
 }
@@ -0,0 +1,256 @@
+use crate::grid::Grid;
+
+// Day 4 says a roll can be reached if fewer than 4 of the 8 rolls around
+// it are there. Both parts had that baked in, so trying something like
+// "only count the 4 rolls that share an edge" or "the floor wraps around"
+// meant changing both of them. These are the rules pulled out on their own
+// so the same code can run any version of the puzzle.
+//
+// There are three things to pick:
+//   - the shape: which cells count as neighbors. Von Neumann is everything
+//     within r steps when you can only move up, down, left, or right, Moore
+//     is the (2r + 1) square around the cell, and custom is whatever list
+//     of offsets you like.
+//   - the threshold: a roll is accessible if fewer than this many of its
+//     neighbors are rolls.
+//   - the edges: bounded means off the grid is just empty floor (what the
+//     puzzle does), wrap means going off one side comes back on the other.
+
+/// The biggest von Neumann or Moore radius the command line will take.
+/// Moore builds (2r + 1)^2 offsets, so there has to be a stop somewhere,
+/// and the real input is under 150 across so anything past this reaches
+/// off every side of it anyway.
+pub const MAX_RADIUS: usize = 150;
+
+/// Which cells around a cell count as its neighbors
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Shape {
+    /// Every cell within this many steps up, down, left, or right. 1 is
+    /// the 4 cells that share an edge.
+    VonNeumann(usize),
+    /// Every cell in the square reaching this far in each direction. 1 is
+    /// the 8 cells around it, the way the puzzle does it.
+    Moore(usize),
+    /// Exactly these (row, col) offsets
+    Custom(Vec<(isize, isize)>),
+}
+
+impl Shape {
+    /// A von Neumann or Moore shape from its name on the command line
+    pub fn parse(name: &str, radius: usize) -> Option<Self> {
+        match name {
+            "von-neumann" => Some(Shape::VonNeumann(radius)),
+            "moore" => Some(Shape::Moore(radius)),
+            _ => None,
+        }
+    }
+
+    /// A custom shape from offsets written like "-1,0 0,1 1,0 0,-1", or
+    /// None if any of them isn't a pair of whole numbers
+    pub fn parse_offsets(text: &str) -> Option<Self> {
+        let offsets = text
+            .split_whitespace()
+            .map(|pair| {
+                let (d_row, d_col) = pair.split_once(',')?;
+                Some((d_row.parse().ok()?, d_col.parse().ok()?))
+            })
+            .collect::<Option<Vec<(isize, isize)>>>()?;
+        Some(Shape::Custom(offsets))
+    }
+
+    /// The (row, col) offsets that make up the shape, top to bottom and
+    /// left to right for von Neumann and Moore. The cell itself is never
+    /// one of its own neighbors unless a custom shape says so.
+    pub fn offsets(&self) -> Vec<(isize, isize)> {
+        let (radius, fits): (usize, fn(usize, usize, usize) -> bool) = match self {
+            Shape::VonNeumann(r) => (*r, |d_row, d_col, r| d_row + d_col <= r),
+            Shape::Moore(r) => (*r, |d_row, d_col, r| d_row.max(d_col) <= r),
+            Shape::Custom(offsets) => return offsets.clone(),
+        };
+
+        let r = radius as isize;
+        let mut offsets = vec![];
+        for d_row in -r..=r {
+            for d_col in -r..=r {
+                if (d_row, d_col) != (0, 0) && fits(d_row.unsigned_abs(), d_col.unsigned_abs(), radius) {
+                    offsets.push((d_row, d_col));
+                }
+            }
+        }
+        offsets
+    }
+}
+
+/// What happens to neighbors that would be off the grid
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Edges {
+    /// They aren't there, so they never count as rolls
+    Bounded,
+    /// They come back round on the other side of the grid
+    Wrap,
+}
+
+/// Everything that decides whether a roll can be reached
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rules {
+    pub shape: Shape,
+    /// A roll is accessible if fewer than this many neighbors are rolls
+    pub threshold: usize,
+    pub edges: Edges,
+}
+
+impl Rules {
+    pub fn new(shape: Shape, threshold: usize, edges: Edges) -> Self {
+        Rules { shape, threshold, edges }
+    }
+
+    /// The rules from the puzzle: fewer than 4 of the 8 neighbors, and
+    /// nothing past the edges
+    pub fn puzzle() -> Self {
+        Rules::new(Shape::Moore(1), 4, Edges::Bounded)
+    }
+
+    /// Work out the offsets once so checking lots of cells doesn't keep
+    /// rebuilding them
+    pub fn neighborhood(&self) -> Neighborhood {
+        Neighborhood { offsets: self.shape.offsets(), threshold: self.threshold, edges: self.edges }
+    }
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Rules::puzzle()
+    }
+}
+
+/// Rules with the shape turned into its offsets, ready to check cells with
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Neighborhood {
+    offsets: Vec<(isize, isize)>,
+    threshold: usize,
+    edges: Edges,
+}
+
+impl Neighborhood {
+    pub fn threshold(&self) -> usize {
+        self.threshold
+    }
+
+    /// How many rolls are around (row, col). Rolls are 1 and floor is 0,
+    /// the same as the day 4 grids.
+    pub fn roll_count(&self, grid: &Grid<usize>, row: usize, col: usize) -> usize {
+        let value = |(x, y)| grid.get(x, y).unwrap();
+        match self.edges {
+            Edges::Bounded => grid.neighbors_at(row, col, &self.offsets).map(value).sum(),
+            Edges::Wrap => grid.wrapping_neighbors_at(row, col, &self.offsets).map(value).sum(),
+        }
+    }
+
+    /// Whether the roll at (row, col) has few enough rolls around it to be
+    /// reached. Doesn't check there's actually a roll there.
+    pub fn is_accessible(&self, grid: &Grid<usize>, row: usize, col: usize) -> bool {
+        self.roll_count(grid, row, col) < self.threshold
+    }
+
+    /// Every roll that can be reached right now
+    pub fn accessible(&self, grid: &Grid<usize>) -> Vec<(usize, usize)> {
+        grid.cells()
+            .filter(|&((row, col), val)| *val == 1 && self.is_accessible(grid, row, col))
+            .map(|(pos, _)| pos)
+            .collect()
+    }
+
+    /// Part 2 under these rules: keep taking away every roll that can be
+    /// reached until none can, and hand back how many went in each round
+    pub fn remove_until_stuck(&self, grid: &mut Grid<usize>) -> Vec<usize> {
+        let mut rounds = vec![];
+        loop {
+            let removed = self.accessible(grid);
+            if removed.is_empty() {
+                return rounds;
+            }
+            for &(row, col) in &removed {
+                grid.set(row, col, 0);
+            }
+            rounds.push(removed.len());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::{EIGHT_NEIGHBORS, FOUR_NEIGHBORS};
+
+    const EXAMPLE: &str = "..@@.@@@@.\n@@@.@.@.@@\n@@@@@.@.@@\n@.@@@@..@.\n@@.@@@@.@@\n.@@@@@@@.@\n.@.@.@.@@@\n@.@@@.@@@@\n.@@@@@@@@.\n@.@.@@@.@.";
+
+    fn rolls(content: &str) -> Grid<usize> {
+        Grid::parse(content, |c| Ok(usize::from(c == '@'))).unwrap()
+    }
+
+    fn sorted(mut offsets: Vec<(isize, isize)>) -> Vec<(isize, isize)> {
+        offsets.sort();
+        offsets
+    }
+
+    #[test]
+    fn shapes() {
+        assert_eq!(sorted(Shape::VonNeumann(1).offsets()), sorted(FOUR_NEIGHBORS.to_vec()));
+        assert_eq!(sorted(Shape::Moore(1).offsets()), sorted(EIGHT_NEIGHBORS.to_vec()));
+        assert_eq!(Shape::VonNeumann(2).offsets().len(), 12);
+        assert_eq!(Shape::Moore(2).offsets().len(), 24);
+        assert!(Shape::Moore(0).offsets().is_empty());
+        assert_eq!(Shape::Custom(vec![(0, 0), (2, -1)]).offsets(), vec![(0, 0), (2, -1)]);
+    }
+
+    #[test]
+    fn parse_shapes() {
+        assert_eq!(Shape::parse("moore", 2), Some(Shape::Moore(2)));
+        assert_eq!(Shape::parse("von-neumann", 1), Some(Shape::VonNeumann(1)));
+        assert_eq!(Shape::parse("hexagon", 1), None);
+        assert_eq!(Shape::parse_offsets("-1,0  0,1\n2,-2"), Some(Shape::Custom(vec![(-1, 0), (0, 1), (2, -2)])));
+        assert_eq!(Shape::parse_offsets("-1,0 0"), None);
+        assert_eq!(Shape::parse_offsets("a,1"), None);
+    }
+
+    #[test]
+    fn the_puzzle_rules() {
+        let mut grid = rolls(EXAMPLE);
+        let neighborhood = Rules::puzzle().neighborhood();
+        assert_eq!(neighborhood.accessible(&grid).len(), 13);
+        assert_eq!(neighborhood.remove_until_stuck(&mut grid).iter().sum::<usize>(), 43);
+    }
+
+    #[test]
+    fn wrapping_edges() {
+        // every roll touches the other 3 once the edges wrap, and twice
+        // in the directions where the grid is only 2 wide
+        let grid = rolls("@@\n@@");
+        let bounded = Rules::new(Shape::Moore(1), 4, Edges::Bounded).neighborhood();
+        let wrapped = Rules::new(Shape::Moore(1), 4, Edges::Wrap).neighborhood();
+        assert_eq!(bounded.roll_count(&grid, 0, 0), 3);
+        assert_eq!(wrapped.roll_count(&grid, 0, 0), 8);
+        assert_eq!(bounded.accessible(&grid).len(), 4);
+        assert!(wrapped.accessible(&grid).is_empty());
+    }
+
+    #[test]
+    fn threshold_and_shape_change_the_answer() {
+        let grid = rolls("@@@\n@@@\n@@@");
+        // the corners have 3 of 8 around them, the edges 5, the middle 8
+        assert_eq!(Rules::new(Shape::Moore(1), 4, Edges::Bounded).neighborhood().accessible(&grid).len(), 4);
+        assert_eq!(Rules::new(Shape::Moore(1), 6, Edges::Bounded).neighborhood().accessible(&grid).len(), 8);
+        // with only edges counting, the corners have 2, the edges 3, the middle 4
+        assert_eq!(Rules::new(Shape::VonNeumann(1), 4, Edges::Bounded).neighborhood().accessible(&grid).len(), 8);
+        assert_eq!(Rules::new(Shape::VonNeumann(1), 0, Edges::Bounded).neighborhood().accessible(&grid).len(), 0);
+    }
+
+    #[test]
+    fn removing_rolls_round_by_round() {
+        let mut grid = rolls("@@@\n@@@\n@@@");
+        let rounds = Rules::puzzle().neighborhood().remove_until_stuck(&mut grid);
+        // the corners go, then the edges have 2 left around them, then the middle
+        assert_eq!(rounds, vec![4, 4, 1]);
+        assert!(grid.find_all(&1).is_empty());
+    }
+}
@@ -0,0 +1,160 @@
+// Range maximum queries: given a list of values, what's the index of the
+// biggest one between lo and hi? Day 3 part 2 asks that over and over
+// while it builds each joltage, and the comments there wondered about a
+// segment tree or a sparse table instead of rescanning the range every
+// time. Here are both.
+//
+// Both of them give back the leftmost index when there's a tie. Day 3
+// needs that, since picking the earlier of two equal digits leaves more
+// digits to choose from afterwards.
+//
+// Ranges are inclusive on both ends, the same way day 3 talks about them.
+
+/// Something that can answer "where is the biggest value in lo..=hi"
+pub trait RangeMax<T> {
+    /// Build whatever the structure needs from the values
+    fn build(values: &[T]) -> Self;
+
+    /// The index of the biggest value in lo..=hi, the leftmost one if
+    /// there's a tie. Panics if the range is empty or out of bounds.
+    fn leftmost_max(&self, lo: usize, hi: usize) -> usize;
+}
+
+/// Whichever of the two indexes has the bigger value, preferring the
+/// smaller index on a tie
+fn better<T: Ord>(values: &[T], a: usize, b: usize) -> usize {
+    match values[a].cmp(&values[b]) {
+        std::cmp::Ordering::Greater => a,
+        std::cmp::Ordering::Less => b,
+        std::cmp::Ordering::Equal => a.min(b),
+    }
+}
+
+/// A bottom up segment tree. O(n) to build, O(log n) per query.
+///
+/// The leaves live in the back half of `tree` (leaf i is at n + i) and
+/// every other slot holds the best index of its two children, so
+/// tree[1] is the best index overall.
+pub struct SegmentTree<T> {
+    values: Vec<T>,
+    tree: Vec<usize>,
+}
+
+impl<T: Ord + Copy> RangeMax<T> for SegmentTree<T> {
+    fn build(values: &[T]) -> Self {
+        let n = values.len();
+        let mut tree = vec![0; 2 * n];
+
+        for i in 0..n {
+            tree[n + i] = i;
+        }
+        for i in (1..n).rev() {
+            tree[i] = better(values, tree[2 * i], tree[2 * i + 1]);
+        }
+
+        SegmentTree { values: values.to_vec(), tree }
+    }
+
+    fn leftmost_max(&self, lo: usize, hi: usize) -> usize {
+        assert!(lo <= hi && hi < self.values.len(), "bad range {}..={}", lo, hi);
+
+        // walk up from both ends of the range at once, picking up any node
+        // that's fully inside the range on the way
+        let n = self.values.len();
+        let mut best = lo;
+        let mut l = lo + n;
+        let mut r = hi + n + 1;
+
+        while l < r {
+            if l % 2 == 1 {
+                best = better(&self.values, best, self.tree[l]);
+                l += 1;
+            }
+            if r % 2 == 1 {
+                r -= 1;
+                best = better(&self.values, best, self.tree[r]);
+            }
+            l /= 2;
+            r /= 2;
+        }
+
+        best
+    }
+}
+
+/// A sparse table. O(n log n) to build and to store, O(1) per query.
+///
+/// table[k][i] is the best index in the 2^k values starting at i. Any
+/// range can be covered by two (possibly overlapping) blocks of the same
+/// power of 2 size, and overlapping doesn't matter for a max.
+pub struct SparseTable<T> {
+    values: Vec<T>,
+    table: Vec<Vec<usize>>,
+}
+
+impl<T: Ord + Copy> RangeMax<T> for SparseTable<T> {
+    fn build(values: &[T]) -> Self {
+        let n = values.len();
+        let mut table = vec![(0..n).collect::<Vec<usize>>()];
+
+        let mut k = 1;
+        while (1 << k) <= n {
+            let half = 1 << (k - 1);
+            let prev = &table[k - 1];
+            let row = (0..=n - (1 << k)).map(|i| better(values, prev[i], prev[i + half])).collect();
+            table.push(row);
+            k += 1;
+        }
+
+        SparseTable { values: values.to_vec(), table }
+    }
+
+    fn leftmost_max(&self, lo: usize, hi: usize) -> usize {
+        assert!(lo <= hi && hi < self.values.len(), "bad range {}..={}", lo, hi);
+
+        let k = (hi - lo + 1).ilog2() as usize;
+        better(&self.values, self.table[k][lo], self.table[k][hi + 1 - (1 << k)])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::cases;
+
+    fn brute_force(values: &[u8], lo: usize, hi: usize) -> usize {
+        (lo..=hi).fold(lo, |best, i| if values[i] > values[best] { i } else { best })
+    }
+
+    #[test]
+    fn leftmost_on_ties() {
+        let values = [1, 9, 3, 9, 9, 2];
+        let segment_tree = SegmentTree::build(&values);
+        let sparse_table = SparseTable::build(&values);
+
+        for (lo, hi, expected) in [(0, 5, 1), (2, 5, 3), (4, 5, 4), (5, 5, 5), (0, 0, 0)] {
+            assert_eq!(segment_tree.leftmost_max(lo, hi), expected);
+            assert_eq!(sparse_table.leftmost_max(lo, hi), expected);
+        }
+    }
+
+    // check every range of a bunch of random lists against just scanning
+    #[test]
+    fn every_range_matches_brute_force() {
+        cases(0x2545f4914f6cdd1d, 50, |rng| {
+            let len = rng.below(40) as usize + 1;
+            // only a few different values so there are lots of ties
+            let values: Vec<u8> = (0..len).map(|_| rng.below(4) as u8).collect();
+            let segment_tree = SegmentTree::build(&values);
+            let sparse_table = SparseTable::build(&values);
+
+            for lo in 0..len {
+                for hi in lo..len {
+                    let expected = brute_force(&values, lo, hi);
+                    assert_eq!(segment_tree.leftmost_max(lo, hi), expected, "segment tree {:?} {}..={}", values, lo, hi);
+                    assert_eq!(sparse_table.leftmost_max(lo, hi), expected, "sparse table {:?} {}..={}", values, lo, hi);
+                }
+            }
+        });
+    }
+}
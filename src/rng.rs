@@ -0,0 +1,63 @@
+// There's no rand crate here, so anything that wants random numbers uses
+// this tiny xorshift generator. It's nowhere near good enough for anything
+// serious but it's plenty for making up test data, and the same seed
+// always gives the same numbers so a failure can be repeated.
+//
+// Most of the property tests look the same: make up a bunch of random
+// cases and check the clever version against something slow and obvious.
+// `cases` is that loop. It's only built for tests, but the generator
+// itself isn't since `crosscheck random` uses it too.
+
+/// An xorshift random number generator
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    /// A generator starting from seed. xorshift gets stuck on 0, so a seed
+    /// of 0 is bumped up to 1.
+    pub fn new(seed: u64) -> Self {
+        Rng(seed.max(1))
+    }
+
+    /// The next random number
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// A random number from 0 up to (but not including) n
+    pub fn below(&mut self, n: u64) -> u64 {
+        self.next_u64() % n
+    }
+
+    /// Heads or tails
+    pub fn coin(&mut self) -> bool {
+        self.next_u64().is_multiple_of(2)
+    }
+}
+
+/// Run check count times, handing it the same generator each time so
+/// every case gets different numbers
+#[cfg(test)]
+pub fn cases(seed: u64, count: usize, mut check: impl FnMut(&mut Rng)) {
+    let mut rng = Rng::new(seed);
+    for _ in 0..count {
+        check(&mut rng);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_same_numbers() {
+        let mut a = Rng::new(0x5eed);
+        let mut b = Rng::new(0x5eed);
+        assert!((0..100).all(|_| a.next_u64() == b.next_u64()));
+        assert_ne!(Rng::new(0).next_u64(), 0);
+        assert!((0..100).all(|_| a.below(7) < 7));
+    }
+}
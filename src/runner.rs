@@ -0,0 +1,128 @@
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use crate::answer::Answer;
+use crate::solver::{Registry, Solver};
+
+// Running one day at a time got old, so this is everything needed to run
+// a whole bunch of solvers in one go: picking which solvers to run from
+// something like "all", "4", or "4-1..7-2", finding their input files,
+// timing them, and printing a little table at the end.
+
+/// Where the input for a given day lives e.g. src/inputs/day4.txt, or
+/// src/inputs/day4-test.txt for the example from the puzzle description.
+pub fn input_path(day: u8, test: bool) -> PathBuf {
+    let file_name = if test {
+        format!("day{}-test.txt", day)
+    } else {
+        format!("day{}.txt", day)
+    };
+
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("src").join("inputs").join(file_name)
+}
+
+/// Where to get the input for a solver from
+pub enum InputSource {
+    /// src/inputs/dayN.txt, or dayN-test.txt if test is true
+    Default { test: bool },
+    /// A specific file
+    Path(PathBuf),
+    /// Whatever gets piped in
+    Stdin,
+}
+
+impl InputSource {
+    /// Read the input for the given day. The error is a message ready
+    /// to show to whoever is running things.
+    pub fn read(&self, day: u8) -> Result<String, String> {
+        let path = match self {
+            InputSource::Default { test } => input_path(day, *test),
+            InputSource::Path(path) => path.clone(),
+            InputSource::Stdin => {
+                return io::read_to_string(io::stdin()).map_err(|e| format!("couldn't read stdin: {}", e))
+            }
+        };
+
+        fs::read_to_string(&path).map_err(|e| format!("couldn't read {}: {}", path.display(), e))
+    }
+}
+
+/// Turn a day-part string like "4-2" into numbers
+fn parse_day_part(val: &str) -> Option<(u8, u8)> {
+    let (day, part) = val.split_once('-')?;
+    Some((day.parse().ok()?, part.parse().ok()?))
+}
+
+/// Pick out the solvers matching a selection:
+///   - "all" runs every day and part
+///   - "4" runs both parts of day 4
+///   - "4-1..7-2" runs everything from day 4 part 1 through day 7 part 2
+///   - "4-2" or "5-1-bst" runs just that one
+///
+/// Alternative versions of a day (the ones with a variant in their key)
+/// are only picked by "all", a day, or a range if variants is true, which
+/// bench uses so the alternatives can be compared in one run. Otherwise
+/// they only get picked by their exact key.
+pub fn select<'a>(registry: &'a Registry, selection: &str, variants: bool) -> Result<Vec<&'a dyn Solver>, String> {
+    let primary = registry.iter().filter(|s| variants || s.variant().is_none());
+
+    let selected: Vec<&dyn Solver> = if selection == "all" {
+        primary.collect()
+    } else if let Some((from, to)) = selection.split_once("..") {
+        let (Some(from), Some(to)) = (parse_day_part(from), parse_day_part(to)) else {
+            return Err(format!("{} isn't a range like 4-1..7-2", selection));
+        };
+        primary.filter(|s| (s.day(), s.part()) >= from && (s.day(), s.part()) <= to).collect()
+    } else if let Ok(day) = selection.parse::<u8>() {
+        primary.filter(|s| s.day() == day).collect()
+    } else {
+        registry.get(selection).into_iter().collect()
+    };
+
+    if selected.is_empty() {
+        return Err(format!("no solvers match {}", selection));
+    }
+
+    Ok(selected)
+}
+
+/// How one solver did on one input
+pub struct RunResult {
+    pub key: String,
+    pub name: &'static str,
+    pub outcome: Result<Answer, String>,
+    pub elapsed: Duration,
+}
+
+/// Read the input for a solver and run it, timing only the solving part
+/// (not reading the file).
+pub fn run(solver: &dyn Solver, input: &InputSource) -> RunResult {
+    let (outcome, elapsed) = match input.read(solver.day()) {
+        Ok(content) => {
+            let start = Instant::now();
+            let outcome = solver.solve(&content).map_err(|e| e.to_string());
+            (outcome, start.elapsed())
+        }
+        Err(e) => (Err(e), Duration::ZERO),
+    };
+
+    RunResult { key: solver.key(), name: solver.name(), outcome, elapsed }
+}
+
+/// Print a table of everything that was run along with the total time
+pub fn print_summary(results: &[RunResult]) {
+    println!("{:<12} {:<48} {:>20} {:>12}", "day-part", "name", "answer", "time");
+
+    for result in results {
+        let answer = match &result.outcome {
+            Ok(answer) => answer.to_string(),
+            Err(e) => format!("error: {}", e),
+        };
+        println!("{:<12} {:<48} {:>20} {:>12?}", result.key, result.name, answer, result.elapsed);
+    }
+
+    let total: Duration = results.iter().map(|r| r.elapsed).sum();
+    println!("{:<12} {:<48} {:>20} {:>12?}", "total", "", "", total);
+}
@@ -0,0 +1,179 @@
+// Every time I added a new day I had to go edit a big match in main.rs
+// and remember what key I gave it. Now each dayNpartM module has a
+// little Solution struct that implements Solver, and the Registry below
+// keeps a list of all of them so the runner can look them up by key,
+// list them, or tell you politely that a key doesn't exist.
+
+use crate::answer::Answer;
+use crate::error::ParseError;
+use crate::neighborhood::Rules;
+
+/// Something that can solve one part of one day's puzzle.
+pub trait Solver {
+    /// The day of the puzzle e.g. 4 for day4part2
+    fn day(&self) -> u8;
+
+    /// The part of the puzzle e.g. 2 for day4part2
+    fn part(&self) -> u8;
+
+    /// A short human readable description of what this solver does
+    fn name(&self) -> &'static str;
+
+    /// Most days only have one way of solving each part, but some have
+    /// alternatives (like the plain BST experiment for day 5). Those
+    /// return a short variant name that becomes part of their key.
+    fn variant(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// The key used on the command line e.g. "4-2" or "5-1-bst"
+    fn key(&self) -> String {
+        match self.variant() {
+            Some(variant) => format!("{}-{}-{}", self.day(), self.part(), variant),
+            None => format!("{}-{}", self.day(), self.part()),
+        }
+    }
+
+    /// Just the parsing step of solve, so benchmarks can time parsing on
+    /// its own. Days that parse as they go don't have a separate step and
+    /// return None.
+    fn parse(&self, _content: &str) -> Option<Result<(), ParseError>> {
+        None
+    }
+
+    /// Solve the puzzle for the given input and hand back the answer, or
+    /// a ParseError pointing at whatever in the input didn't make sense
+    fn solve(&self, content: &str) -> Result<Answer, ParseError>;
+
+    /// Some solvers build an answer out of a configurable number of pieces
+    /// (like how many batteries day 3 turns on in each bank). Those hand
+    /// back a copy of themselves using the new length, everything else
+    /// returns None.
+    fn with_length(&self, _length: usize) -> Option<Box<dyn Solver>> {
+        None
+    }
+
+    /// Same idea as with_length, for days that decide things by looking
+    /// at a cell's neighbors (like day 4's "fewer than 4 of the 8 rolls
+    /// around it"). Those hand back a copy of themselves using the new
+    /// rules, everything else returns None.
+    fn with_rules(&self, _rules: &Rules) -> Option<Box<dyn Solver>> {
+        None
+    }
+
+    /// A look at how the answer was put together (like which batteries
+    /// day 3 turned on in each bank) for `--report`. Most days don't have
+    /// anything to show and return None.
+    fn report(&self, _content: &str, _options: ReportOptions) -> Option<Result<String, ParseError>> {
+        None
+    }
+}
+
+/// The ways results (benchmarks, reports, and so on) can be printed. Table
+/// is for people, csv and json are for feeding into something else.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Table,
+    Csv,
+    Json,
+}
+
+impl OutputFormat {
+    pub fn parse(val: &str) -> Option<Self> {
+        match val {
+            "table" => Some(OutputFormat::Table),
+            "csv" => Some(OutputFormat::Csv),
+            "json" => Some(OutputFormat::Json),
+            _ => None,
+        }
+    }
+}
+
+/// How a report should be shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReportOptions {
+    pub format: OutputFormat,
+    /// Reports that list things (like day 2's invalid IDs) only show
+    /// this many of them. If it's None the report picks its own limit.
+    pub limit: Option<usize>,
+}
+
+impl ReportOptions {
+    pub fn new(format: OutputFormat) -> Self {
+        ReportOptions { format, limit: None }
+    }
+}
+
+/// A list of all of the solvers we know about, kept sorted by day, part,
+/// and variant so listing them comes out in a sensible order.
+pub struct Registry {
+    solvers: Vec<Box<dyn Solver>>,
+}
+
+impl Registry {
+    /// An empty registry. Most of the time you want Registry::default()
+    /// which has every day registered.
+    pub fn new() -> Self {
+        Registry { solvers: vec![] }
+    }
+
+    /// Add a solver to the registry. Panics if the key is already taken
+    /// since that means two modules think they are the same day/part.
+    pub fn register(&mut self, solver: Box<dyn Solver>) {
+        if self.get(&solver.key()).is_some() {
+            panic!("a solver is already registered for {}", solver.key());
+        }
+
+        self.solvers.push(solver);
+        self.solvers.sort_by_key(|s| (s.day(), s.part(), s.variant()));
+    }
+
+    /// Look up a solver by its key e.g. "4-2"
+    pub fn get(&self, key: &str) -> Option<&dyn Solver> {
+        self.solvers.iter().find(|s| s.key() == key).map(|s| s.as_ref())
+    }
+
+    /// All of the registered solvers in day/part order
+    pub fn iter(&self) -> impl Iterator<Item = &dyn Solver> {
+        self.solvers.iter().map(|s| s.as_ref())
+    }
+
+    /// All of the registered keys in day/part order
+    pub fn keys(&self) -> Vec<String> {
+        self.iter().map(|s| s.key()).collect()
+    }
+}
+
+/// The registry with every day and part registered. When adding a new
+/// day, this is the only place (other than lib.rs) that needs updating.
+impl Default for Registry {
+    fn default() -> Self {
+        let mut registry = Registry::new();
+
+        registry.register(Box::new(crate::day1part1::Solution));
+        registry.register(Box::new(crate::day1part2::Solution));
+        registry.register(Box::new(crate::day2part1::Solution));
+        registry.register(Box::new(crate::day2part1::SeriesSolution));
+        registry.register(Box::new(crate::day2part2::Solution));
+        registry.register(Box::new(crate::day2part2::SeriesSolution));
+        registry.register(Box::new(crate::day3part1::Solution));
+        registry.register(Box::new(crate::day3part2::Solution));
+        registry.register(Box::new(crate::day3part2::MethodSolution::new(crate::day3part2::Method::SegmentTree)));
+        registry.register(Box::new(crate::day3part2::MethodSolution::new(crate::day3part2::Method::SparseTable)));
+        registry.register(Box::new(crate::day3part2::MethodSolution::new(crate::day3part2::Method::Stack)));
+        registry.register(Box::new(crate::day4part1::Solution::default()));
+        registry.register(Box::new(crate::day4part2::Solution::default()));
+        registry.register(Box::new(crate::day5part01::Solution));
+        registry.register(Box::new(crate::day5part1::Solution));
+        registry.register(Box::new(crate::day5part1::TreeSolution));
+        registry.register(Box::new(crate::day5part2::Solution));
+        registry.register(Box::new(crate::day5part2::TreeSolution));
+        registry.register(Box::new(crate::day6part1::Solution));
+        registry.register(Box::new(crate::day6part2::Solution));
+        registry.register(Box::new(crate::day7part1::Solution));
+        registry.register(Box::new(crate::day7part2::Solution));
+        registry.register(Box::new(crate::day9part1::Solution));
+
+        registry
+    }
+}
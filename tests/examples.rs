@@ -0,0 +1,173 @@
+// Run every solver on the example input from its puzzle description
+// (src/inputs/dayN-test.txt) and check that it gets the answer the
+// puzzle says it should.
+
+use std::fs;
+
+use aoc::answer::Answer;
+use aoc::expected::ExpectedAnswers;
+use aoc::neighborhood::{Edges, Rules, Shape};
+use aoc::runner::{self, input_path};
+use aoc::solver::{OutputFormat, Registry, ReportOptions};
+
+fn solve_example(key: &str) -> Answer {
+    let registry = Registry::default();
+    let solver = registry.get(key).unwrap_or_else(|| panic!("no solver registered for {}", key));
+    let content = fs::read_to_string(input_path(solver.day(), true)).unwrap();
+
+    solver.solve(&content).unwrap()
+}
+
+#[test]
+fn day1part1() {
+    assert_eq!(solve_example("1-1"), Answer::from("3 (final dial position 32)"));
+}
+
+#[test]
+fn day1part2() {
+    assert_eq!(solve_example("1-2"), Answer::from("6 (final dial position 32)"));
+}
+
+#[test]
+fn day2part1() {
+    assert_eq!(solve_example("2-1"), Answer::Int(1227775554));
+}
+
+#[test]
+fn day2part2() {
+    assert_eq!(solve_example("2-2"), Answer::Int(4174379265));
+}
+
+#[test]
+fn day2_series() {
+    assert_eq!(solve_example("2-1-series"), Answer::Int(1227775554));
+    assert_eq!(solve_example("2-2-series"), Answer::Int(4174379265));
+}
+
+#[test]
+fn day3part1() {
+    assert_eq!(solve_example("3-1"), Answer::Int(357));
+}
+
+#[test]
+fn day3part2() {
+    assert_eq!(solve_example("3-2"), Answer::Int(3121910778619));
+}
+
+#[test]
+fn day3part2_methods() {
+    for key in ["3-2-segtree", "3-2-sparse", "3-2-stack"] {
+        assert_eq!(solve_example(key), Answer::Int(3121910778619), "{}", key);
+    }
+}
+
+#[test]
+fn day3_reports() {
+    let registry = Registry::default();
+    let content = fs::read_to_string(input_path(3, true)).unwrap();
+
+    let report = registry.get("3-1").unwrap().report(&content, ReportOptions::new(OutputFormat::Csv)).unwrap().unwrap();
+    assert_eq!(report.lines().nth(4), Some("4,92,\"7 12\""));
+
+    let report = registry.get("3-2").unwrap().report(&content, ReportOptions::new(OutputFormat::Csv)).unwrap().unwrap();
+    assert_eq!(report.lines().nth(1), Some("1,987654321111,\"1 2 3 4 5 6 7 8 9 10 11 12\""));
+
+    assert!(registry.get("4-1").unwrap().report(&content, ReportOptions::new(OutputFormat::Csv)).is_none());
+}
+
+#[test]
+fn day4part1() {
+    assert_eq!(solve_example("4-1"), Answer::Int(13));
+}
+
+#[test]
+fn day4part2() {
+    assert_eq!(solve_example("4-2"), Answer::Int(43));
+}
+
+#[test]
+fn day4_rules() {
+    let registry = Registry::default();
+    let content = fs::read_to_string(input_path(4, true)).unwrap();
+    let solve = |key: &str, rules: &Rules| registry.get(key).unwrap().with_rules(rules).unwrap().solve(&content).unwrap();
+
+    assert_eq!(solve("4-1", &Rules::puzzle()), Answer::Int(13));
+    assert_eq!(solve("4-2", &Rules::puzzle()), Answer::Int(43));
+    assert_eq!(solve("4-2", &Rules::new(Shape::Moore(1), 4, Edges::Wrap)), Answer::Int(3));
+    assert_eq!(solve("4-2", &Rules::new(Shape::VonNeumann(1), 2, Edges::Bounded)), Answer::Int(16));
+    assert!(registry.get("3-1").unwrap().with_rules(&Rules::puzzle()).is_none());
+}
+
+#[test]
+fn day5part1() {
+    assert_eq!(solve_example("5-1"), Answer::Int(3));
+}
+
+#[test]
+fn day5part1_avl() {
+    assert_eq!(solve_example("5-1-avl"), Answer::Int(3));
+}
+
+#[test]
+fn day5part2() {
+    assert_eq!(solve_example("5-2"), Answer::Int(14));
+}
+
+#[test]
+fn day5part2_avl() {
+    assert_eq!(solve_example("5-2-avl"), Answer::Int(14));
+}
+
+#[test]
+fn day6part1() {
+    assert_eq!(solve_example("6-1"), Answer::Int(4277556));
+}
+
+#[test]
+fn day6part2() {
+    assert_eq!(solve_example("6-2"), Answer::Int(3263827));
+}
+
+#[test]
+fn day7part1() {
+    assert_eq!(solve_example("7-1"), Answer::Int(21));
+}
+
+#[test]
+fn day7part2() {
+    assert_eq!(solve_example("7-2"), Answer::Int(40));
+}
+
+#[test]
+fn day9part1() {
+    assert_eq!(solve_example("9-1"), Answer::Int(50));
+}
+
+#[test]
+fn every_solver_has_an_example_answer() {
+    let registry = Registry::default();
+    let expected = ExpectedAnswers::load().unwrap();
+
+    for key in registry.keys() {
+        assert!(expected.test.contains_key(&key), "answers.toml has no [test] answer for {}", key);
+    }
+}
+
+// bench compares the alternatives, so a day picks up its variants there,
+// but run sticks to the main solver for each part
+#[test]
+fn bench_selections_include_variants() {
+    let registry = Registry::default();
+    let keys = |selection: &str, variants: bool| -> Vec<String> {
+        runner::select(&registry, selection, variants).unwrap().iter().map(|s| s.key()).collect()
+    };
+
+    let bench = keys("3", true);
+    assert!(bench.contains(&"3-2-segtree".to_string()));
+    assert!(bench.contains(&"3-2".to_string()));
+    assert!(keys("all", true).contains(&"5-1-bst".to_string()));
+    assert!(keys("2-1..2-2", true).contains(&"2-2-series".to_string()));
+
+    assert_eq!(keys("3", false), vec!["3-1", "3-2"]);
+    assert_eq!(keys("3-2-segtree", false), vec!["3-2-segtree"]);
+}